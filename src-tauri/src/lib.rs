@@ -1,17 +1,138 @@
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::{BufWriter, Cursor, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, State};
+use notify::Watcher;
 use walkdir::WalkDir;
 use rayon::prelude::*;
 use zip::ZipArchive;
 
+/// 按 `MVNCLEAN_LANG` 环境变量输出中文（默认）或英文日志；
+/// 当前线程处于 `with_log_capture` 中时同时收集该行
+macro_rules! log_msg {
+    ($zh:literal, $en:literal $(, $arg:expr)* $(,)?) => {{
+        let line = log_line(
+            || format!($zh $(, $arg)*),
+            || format!($en $(, $arg)*),
+            use_english_logs(),
+        );
+        println!("{}", line);
+        capture_log_line(line);
+    }};
+}
+
 // ===================== 数据结构 =====================
 
+/// 构件被判定为损坏的原因，序列化为稳定的机器可读代码，便于前端筛选和国际化
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    CorruptZip,
+    Unreadable,
+    HarborPom,
+    NexusError,
+    AbnormalMtime,
+    StaleLock,
+    CorruptMetadata,
+    ManifestMismatch,
+    ValidatorFailed,
+    ChecksumMismatch,
+    PartialDownload,
+    EntryCrcMismatch,
+    ConflictingChecksums,
+    ArtifactoryError,
+    TooSmall,
+    EmptyJar,
+    HttpHeaders,
+    MissingMainJar,
+    SizeMismatch,
+    MissingParentPom,
+    GzipJar,
+    EmptyChecksum,
+    VersionMismatch,
+}
+
+impl Reason {
+    /// 默认的中文说明
+    fn message(self) -> &'static str {
+        match self {
+            Reason::CorruptZip => "ZIP 格式损坏",
+            Reason::Unreadable => "无法读取文件",
+            Reason::HarborPom => "包含错误页面的POM文件",
+            Reason::NexusError => "Nexus缓存错误响应",
+            Reason::AbnormalMtime => "文件时间戳异常",
+            Reason::StaleLock => "残留锁文件",
+            Reason::CorruptMetadata => "maven-metadata损坏",
+            Reason::ManifestMismatch => "JAR内容与坐标不符",
+            Reason::ValidatorFailed => "外部校验失败",
+            Reason::ChecksumMismatch => "与远程校验和不一致",
+            Reason::PartialDownload => "未完成的下载临时文件",
+            Reason::EntryCrcMismatch => "JAR内部条目CRC校验失败",
+            Reason::ConflictingChecksums => "冲突的校验和文件",
+            Reason::ArtifactoryError => "Artifactory错误响应",
+            Reason::TooSmall => "JAR文件过小",
+            Reason::EmptyJar => "空JAR(无类文件)",
+            Reason::HttpHeaders => "文件含HTTP响应头",
+            Reason::MissingMainJar => "POM声明为jar但缺少主JAR",
+            Reason::SizeMismatch => "文件大小与预期不符",
+            Reason::MissingParentPom => "父POM缺失",
+            Reason::GzipJar => "JAR实际为gzip数据",
+            Reason::EmptyChecksum => "空校验和文件",
+            Reason::VersionMismatch => "版本与目录不符",
+        }
+    }
+}
+
+/// 一次检测命中的结果：原因代码及给用户看的说明
+#[derive(Debug, Clone)]
+struct Finding {
+    reason: Reason,
+    message: String,
+}
+
+impl Finding {
+    fn new(reason: Reason) -> Self {
+        Self {
+            reason,
+            message: reason.message().to_string(),
+        }
+    }
+
+    /// 在默认说明后附加具体细节（如底层错误信息）
+    fn with_detail(reason: Reason, detail: impl std::fmt::Display) -> Self {
+        Self {
+            reason,
+            message: format!("{}: {}", reason.message(), detail),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvalidArtifact {
     folder: String,
     base_name: String,
-    reason: String,
+    reason: Reason,
+    /// 本地化的可读说明
+    message: String,
+}
+
+impl InvalidArtifact {
+    fn new(folder: String, base_name: String, finding: Finding) -> Self {
+        Self {
+            folder,
+            base_name,
+            reason: finding.reason,
+            message: finding.message,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,384 +141,6069 @@ pub struct CleanItem {
     base_name: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct CleanResult {
-    deleted_count: usize,
-    errors: Vec<String>,
+/// 一组私服错误页面关键词及命中时报告的原因
+struct KeywordGroup {
+    keywords: &'static [&'static str],
+    /// 是否同时识别 `{"errors":[{"status":...,"message":...}]}` 形式的 JSON 错误响应
+    json_errors: bool,
+    reason: Reason,
 }
 
-// ===================== 常量配置 =====================
-
-const BAD_POM_KEYWORDS: &[&str] = &[
-    "<!DOCTYPE html>",
-    "<title>Harbor</title>",
-    "Login to Harbor",
-];
-const METADATA_FILES: &[&str] = &[
-    "_remote.repositories",
-    "_maven.repositories",
-    "resolver-status.properties",
-];
-
-// ===================== Tauri Commands =====================
-
-#[tauri::command]
-fn get_maven_repo_path() -> Result<String, String> {
-    // 辅助函数：从 settings.xml 解析 localRepository
-    fn parse_local_repo(settings_path: &Path) -> Option<String> {
-        println!("[DEBUG] 尝试读取配置文件: {}", settings_path.display());
+/// 清理脚本的目标平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptPlatform {
+    Bash,
+    Powershell,
+}
 
-        if !settings_path.exists() {
-            println!("[DEBUG] 文件不存在");
-            return None;
+impl ScriptPlatform {
+    fn header(self) -> &'static str {
+        match self {
+            ScriptPlatform::Bash => "#!/usr/bin/env bash\n# 由 MavenGo 生成的清理脚本，请检查后再执行\nset -u\n",
+            ScriptPlatform::Powershell => "# 由 MavenGo 生成的清理脚本，请检查后再执行\n",
         }
+    }
 
-        let content = match fs::read_to_string(settings_path) {
-            Ok(c) => {
-                println!("[DEBUG] 文件读取成功，长度: {} 字节", c.len());
-                c
-            }
-            Err(e) => {
-                println!("[DEBUG] 文件读取失败: {}", e);
-                return None;
-            }
-        };
-
-        let doc = match roxmltree::Document::parse(&content) {
-            Ok(d) => {
-                println!("[DEBUG] XML 解析成功");
-                d
-            }
-            Err(e) => {
-                println!("[DEBUG] XML 解析失败: {}", e);
-                return None;
-            }
-        };
-
-        for node in doc.descendants() {
-            if node.has_tag_name("localRepository") {
-                if let Some(repo_path) = node.text() {
-                    let trimmed = repo_path.trim();
-                    if !trimmed.is_empty() {
-                        println!("[DEBUG] ✅ 找到 localRepository: {}", trimmed);
-                        return Some(trimmed.to_string());
-                    }
-                }
+    /// 生成删除单个文件的命令，路径按平台规则加引号转义
+    fn remove_command(self, path: &str) -> String {
+        match self {
+            ScriptPlatform::Bash => format!("rm -f -- '{}'", path.replace('\'', r"'\''")),
+            ScriptPlatform::Powershell => {
+                // PowerShell 把弯引号也当作单引号，需要一并双写转义
+                let escaped: String = path
+                    .chars()
+                    .flat_map(|c| match c {
+                        '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' => vec![c, c],
+                        _ => vec![c],
+                    })
+                    .collect();
+                format!("Remove-Item -LiteralPath '{}' -Force", escaped)
             }
         }
+    }
 
-        println!("[DEBUG] 未找到 <localRepository> 标签");
-        None
+    fn comment(self, text: &str) -> String {
+        format!("# {}", text.replace('\n', " "))
     }
+}
 
-    // 辅助函数：通过 mvn -v 命令获取 Maven 安装路径
-    fn get_maven_home_from_command() -> Option<String> {
-        use std::process::Command;
+/// 清理选项，所有字段均有默认值
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CleanOptions {
+    /// 目标为版本目录且只包含该构件的文件时，直接删除整个版本目录
+    remove_whole_version_dir: bool,
+    /// 是否同时删除目录中的元数据文件（`_remote.repositories` 等）
+    delete_metadata: bool,
+    /// 遇到第一个删除失败时立即停止，返回已删除的数量和该错误，避免部分清理后继续
+    fail_fast: bool,
+    /// 删除因文件被短暂占用（索引、杀毒软件等）失败时的最多尝试次数，至少 1 次
+    delete_attempts: u32,
+}
 
-        println!("[DEBUG] 尝试执行 mvn 命令");
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            remove_whole_version_dir: false,
+            delete_metadata: true,
+            fail_fast: false,
+            delete_attempts: 3,
+        }
+    }
+}
 
-        // Windows 下尝试 mvn.cmd 和 mvn.bat
-        let maven_commands = if cfg!(target_os = "windows") {
-            vec!["mvn.cmd", "mvn.bat", "mvn"]
-        } else {
-            vec!["mvn"]
-        };
+#[derive(Debug, Serialize)]
+pub struct CleanResult {
+    /// 删除的构件文件数（JAR、POM 及其校验和等）
+    artifacts_deleted: usize,
+    /// 删除的元数据文件数（`_remote.repositories` 等）
+    metadata_deleted: usize,
+    errors: Vec<String>,
+}
 
-        for cmd in maven_commands {
-            println!("[DEBUG] 尝试命令: {}", cmd);
+/// `scan_and_clean` 的结果：扫描报告及随后的清理结果
+#[derive(Debug, Serialize)]
+pub struct ScanAndCleanResult {
+    report: ScanReport,
+    clean: CleanResult,
+}
 
-            // 创建命令构建器
-            let mut command = Command::new(cmd);
-            command.arg("-v");
+#[derive(Debug, Serialize)]
+pub struct PurgeResult {
+    /// 删除的校验和文件数
+    checksums_deleted: usize,
+    errors: Vec<String>,
+}
 
-            // Windows 下隐藏命令行窗口
-            #[cfg(target_os = "windows")]
-            {
-                use std::os::windows::process::CommandExt;
-                const CREATE_NO_WINDOW: u32 = 0x08000000;
-                command.creation_flags(CREATE_NO_WINDOW);
-            }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanReport {
+    artifacts: Vec<InvalidArtifact>,
+    /// 各检测原因对应的构件数量
+    reason_counts: HashMap<Reason, usize>,
+    /// 实际检查的文件数
+    files_scanned: usize,
+    /// 扫描总耗时（毫秒）
+    duration_ms: u64,
+    /// 文件收集阶段耗时（毫秒）
+    collect_ms: u64,
+    /// 并行检查阶段耗时（毫秒）
+    check_ms: u64,
+    /// 实际使用的扫描线程数
+    thread_count: usize,
+    /// 发现的损坏构件总数，`truncated` 时大于 `artifacts` 的长度
+    total_invalid: usize,
+    /// 结果是否因 `max_results` 被截断
+    truncated: bool,
+}
 
-            let output = match command.output() {
-                Ok(o) => {
-                    println!("[DEBUG] {} 执行成功", cmd);
-                    o
-                }
-                Err(e) => {
-                    println!("[DEBUG] {} 执行失败: {}", cmd, e);
-                    continue;
-                }
-            };
+/// 扫描完成时通过 `scan-complete` 事件发送的汇总信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanSummary {
+    total_files: usize,
+    invalid_count: usize,
+    duration_ms: u64,
+    reason_counts: HashMap<Reason, usize>,
+}
 
-            if !output.status.success() {
-                println!("[DEBUG] {} 返回非零状态码", cmd);
-                continue;
-            }
+impl From<&ScanReport> for ScanSummary {
+    fn from(report: &ScanReport) -> Self {
+        Self {
+            total_files: report.files_scanned,
+            invalid_count: report.total_invalid,
+            duration_ms: report.duration_ms,
+            reason_counts: report.reason_counts.clone(),
+        }
+    }
+}
 
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            println!("[DEBUG] mvn -v 输出:\n{}", stdout);
+#[derive(Debug, Serialize)]
+pub struct RemoteVerifyResult {
+    mismatches: Vec<InvalidArtifact>,
+    checked_count: usize,
+    not_found_count: usize,
+    errors: Vec<String>,
+}
 
-            for line in stdout.lines() {
-                if line.starts_with("Maven home:") {
-                    let maven_home = line.strip_prefix("Maven home:")?.trim();
-                    println!("[DEBUG] ✅ 从 mvn -v 解析到 Maven home: {}", maven_home);
-                    return Some(maven_home.to_string());
-                }
-            }
-        }
+/// 某个远程仓库提供的构件统计
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteSourceStat {
+    /// `_remote.repositories` 中记录的仓库 id，本地安装的构件记为 `(local)`
+    remote_id: String,
+    artifact_count: usize,
+    /// 其中被判定为损坏的构件数
+    invalid_count: usize,
+}
 
-        println!("[DEBUG] 所有 mvn 命令尝试均失败");
-        None
-    }
+/// 仓库路径的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RepoSource {
+    /// 用户手动指定的路径
+    Override,
+    /// 调用方传入的 settings.xml
+    CustomSettings,
+    /// `mvn -v` 找到的 Maven 全局配置
+    MvnCommand,
+    /// `MAVEN_HOME` / `M2_HOME` 或 PATH 推断出的 Maven 全局配置
+    EnvVar,
+    /// `~/.m2/settings.xml`
+    UserSettings,
+    /// 未检测到任何配置，使用 `~/.m2/repository`
+    Default,
+}
 
-    println!("\n========== 开始检测 Maven 仓库路径 ==========");
+/// 仓库路径检测结果，前端可据此在只是猜测默认路径时提示用户
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoLocation {
+    path: String,
+    source: RepoSource,
+    /// 是否找到了 Maven 安装；手动指定路径或传入配置文件时不做检测，为 false
+    maven_found: bool,
+    /// 逐步检测过程的日志，供前端展示以便排查检测结果
+    log: Vec<String>,
+}
 
-    // 1. 最高优先级：通过 mvn -v 命令获取的 Maven 全局配置
-    println!("[步骤 1] 尝试通过 mvn -v 命令检测");
-    if let Some(maven_home) = get_maven_home_from_command() {
-        let global_settings = Path::new(&maven_home).join("conf").join("settings.xml");
-        if let Some(repo) = parse_local_repo(&global_settings) {
-            println!("========== ✅ 检测成功，返回路径: {} ==========\n", repo);
-            return Ok(repo);
+impl RepoLocation {
+    fn new(path: String, source: RepoSource, maven_found: bool) -> Self {
+        Self {
+            path,
+            source,
+            maven_found,
+            log: Vec::new(),
         }
     }
+}
 
-    // 2. 次优先级：环境变量指定的 Maven 全局配置
-    println!("[步骤 2] 尝试读取环境变量 MAVEN_HOME / M2_HOME");
-    let mut maven_home_candidates = vec![
-        std::env::var("MAVEN_HOME").ok(),
-        std::env::var("M2_HOME").ok(),
-    ];
+/// 某个 groupId 前缀下的磁盘占用
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupSize {
+    /// 按深度截取的 groupId 前缀，如 `org`、`com.google`；仓库根目录下的文件记为 `(root)`
+    group: String,
+    size: u64,
+    file_count: usize,
+}
 
-    for (i, maven_home) in maven_home_candidates.iter().enumerate() {
-        if let Some(home) = maven_home {
-            println!("[DEBUG] 环境变量 {} = {}", if i == 0 { "MAVEN_HOME" } else { "M2_HOME" }, home);
-        } else {
-            println!("[DEBUG] 环境变量 {} 未设置", if i == 0 { "MAVEN_HOME" } else { "M2_HOME" });
-        }
-    }
+/// 某个 groupId 下构件的新旧程度
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupAge {
+    group: String,
+    /// 最旧构件的修改时间（Unix 秒）
+    oldest_mtime: u64,
+    /// 最新构件的修改时间（Unix 秒）
+    newest_mtime: u64,
+    artifact_count: usize,
+}
 
-    // 3. 尝试从 PATH 环境变量推断 Maven 路径
-    println!("[步骤 2.5] 尝试从 PATH 环境变量推断 Maven 路径");
-    if let Ok(path_env) = std::env::var("PATH") {
-        println!("[DEBUG] PATH 环境变量已设置");
-        for path in path_env.split(';') {
-            if path.to_lowercase().contains("maven") && path.to_lowercase().contains("bin") {
-                println!("[DEBUG] 发现可能的 Maven bin 目录: {}", path);
-                // Maven bin 路径的父目录就是 Maven home
-                if let Some(parent) = Path::new(path).parent() {
-                    let maven_home = parent.to_string_lossy().to_string();
-                    println!("[DEBUG] 推断的 Maven home: {}", maven_home);
-                    maven_home_candidates.push(Some(maven_home));
-                }
-            }
+/// 仅大小写不同的一组路径，在不区分大小写的文件系统上会互相覆盖
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseCollision {
+    /// 各个实际存在的路径，已排序
+    paths: Vec<String>,
+}
+
+/// 长期未被访问的构件
+#[derive(Debug, Clone, Serialize)]
+pub struct UnusedArtifact {
+    folder: String,
+    base_name: String,
+    size: u64,
+    /// 最近一次访问距今的天数
+    idle_days: u64,
+}
+
+/// 参与扫描的构件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArtifactType {
+    Jar,
+    Pom,
+}
+
+impl ArtifactType {
+    const ALL: [ArtifactType; 2] = [ArtifactType::Jar, ArtifactType::Pom];
+
+    fn extension(self) -> &'static str {
+        match self {
+            ArtifactType::Jar => "jar",
+            ArtifactType::Pom => "pom",
         }
     }
+}
 
-    for maven_home in maven_home_candidates.into_iter().flatten() {
-        let global_settings = Path::new(&maven_home).join("conf").join("settings.xml");
-        if let Some(repo) = parse_local_repo(&global_settings) {
-            println!("========== ✅ 检测成功，返回路径: {} ==========\n", repo);
-            return Ok(repo);
+/// 扫描配置，所有字段均有默认值，前端可只传需要修改的字段
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// 是否检测修改时间异常（过早或处于未来）的构件
+    check_mtime: bool,
+    /// 修改时间下限年份，早于该年份 1 月 1 日的文件视为异常
+    mtime_floor_year: i32,
+    /// POM 关键词检测时读取的文件头部字节数
+    pom_preview_bytes: usize,
+    /// 只扫描指定类型的构件，默认 JAR 和 POM 都扫描
+    artifact_types: Vec<ArtifactType>,
+    /// 视为构件的文件扩展名（不含点），如 `war`、`aar`、`module`；
+    /// `jar` / `pom` 还需同时包含在 `artifact_types` 中
+    artifact_extensions: Vec<String>,
+    /// 是否检测残留的 `.lock` / `.nfs*` 锁文件
+    detect_lock_files: bool,
+    /// 锁文件最近修改距今少于该秒数时视为仍被占用，不予报告
+    lock_min_age_secs: u64,
+    /// 是否检测中断下载遗留的 `.part` / `.tmp` / `.in_progress` 文件
+    detect_temp_files: bool,
+    /// 是否检测损坏的 `maven-metadata*.xml`
+    check_metadata: bool,
+    /// 是否检测同一构件存在多个内容不一致的同算法校验和文件
+    check_checksum_conflicts: bool,
+    /// 是否检测零字节的校验和文件（中断下载遗留，会导致严格校验的构建失败）
+    check_empty_checksums: bool,
+    /// 发现零字节校验和文件时，按相邻构件重新计算摘要并写入，而不是报告
+    regenerate_empty_checksums: bool,
+    /// 是否检测通用 HTML / Harbor 错误页面
+    harbor_keywords: bool,
+    /// 是否检测 Nexus 缓存的错误响应
+    nexus_keywords: bool,
+    /// 是否检测 Artifactory 缓存的 404 等错误响应（HTML 或 JSON）
+    artifactory_keywords: bool,
+    /// 是否检测文件开头混入的原始 HTTP 响应头（`HTTP/1.1 200 OK`、`Content-Type:` 等）
+    check_http_headers: bool,
+    /// 快速模式：只按文件名和大小检测（过小的 JAR、空校验和文件、锁文件、临时文件、
+    /// 开启时的版本目录不符），适合网络挂载等读取较慢的仓库
+    quick: bool,
+    /// 小于该字节数的 JAR 视为损坏（`jar_size_thresholds` 未覆盖时的默认值）
+    quick_min_jar_bytes: u64,
+    /// 按 classifier 设置的 JAR 大小下限，空字符串表示主 JAR，值为 0 表示不检查；
+    /// 未列出的 classifier 使用 `quick_min_jar_bytes`
+    jar_size_thresholds: HashMap<String, u64>,
+    /// 已知有效的小 JAR 的 SHA-1，低于大小下限但摘要匹配时不报告；
+    /// 文件内容一旦变化就会重新被标记
+    size_exempt_sha1: Vec<String>,
+    /// 是否逐个解压 JAR 条目校验 CRC（耗时较长，默认关闭）
+    deep_verify: bool,
+    /// 是否检测除 `META-INF/` 外没有任何条目的空 JAR
+    check_empty_jar: bool,
+    /// 是否将 JAR 的实际大小与 `_remote.repositories` 中记录的预期大小比对（无记录时跳过）
+    check_recorded_size: bool,
+    /// 是否检测 packaging 为 jar（或未声明）但目录中没有对应主 JAR 的 POM；
+    /// Maven 解析依赖冲突时只下载落选版本的 POM，这类情况较常见，默认关闭
+    check_missing_jar: bool,
+    /// 是否检测 `<parent>` 指向的父 POM 在仓库中不存在的 POM（依赖树下载不完整）
+    check_parent_pom: bool,
+    /// 是否检测文件名中的版本与所在版本目录不符的构件（如 `1.0/` 下的 `foo-1.1.jar`）；
+    /// 版本只从文件名推断，artifactId 含版本号样式的片段时可能误判，默认关闭
+    check_version_mismatch: bool,
+    /// 是否比对 JAR 的 MANIFEST 声明与路径中的 artifactId
+    check_manifest: bool,
+    /// 扫描时同时打开的文件数上限，避免 `ulimit -n` 较低时出现 "too many open files"
+    max_open_files: usize,
+    /// 扫描线程数，未设置时为 CPU 核心数 * 4
+    thread_count: Option<usize>,
+    /// 最多返回的损坏构件数（按目录和基础名排序后取前 N 个），防止结果过多拖垮界面
+    max_results: Option<usize>,
+    /// 仓库中的文件数超过该值时拒绝扫描，防止检测或手动指定的路径出错时误扫整个主目录
+    max_files: usize,
+    /// 跳过 `max_files` 和仓库路径形态的安全检查，强制扫描
+    force: bool,
+    /// 调试日志文件路径（可选），设置后为每个检查的文件写入一行 JSON，记录执行的
+    /// 检查项和结论，用于排查漏报；会降低扫描速度，默认关闭
+    debug_log_path: Option<String>,
+    /// 外部校验命令（可选），`{path}` 会被替换为构件路径，非零退出码视为损坏
+    validator_cmd: Option<String>,
+    /// 外部校验命令的最大并发数
+    validator_concurrency: usize,
+    /// 外部校验命令的超时时间（秒）
+    validator_timeout_secs: u64,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            check_mtime: false,
+            mtime_floor_year: 2000,
+            pom_preview_bytes: 1024,
+            artifact_types: ArtifactType::ALL.to_vec(),
+            artifact_extensions: vec!["jar".to_string(), "pom".to_string()],
+            detect_lock_files: true,
+            lock_min_age_secs: 300,
+            detect_temp_files: true,
+            check_metadata: true,
+            check_checksum_conflicts: true,
+            check_empty_checksums: true,
+            regenerate_empty_checksums: false,
+            harbor_keywords: true,
+            nexus_keywords: true,
+            artifactory_keywords: true,
+            check_http_headers: true,
+            quick: false,
+            quick_min_jar_bytes: QUICK_MIN_JAR_BYTES,
+            // 主 JAR 小于 1KB 几乎总是损坏的，源码和文档 JAR 本来就可能很小
+            jar_size_thresholds: HashMap::from([
+                (String::new(), 1024),
+                ("sources".to_string(), 0),
+                ("javadoc".to_string(), 0),
+            ]),
+            size_exempt_sha1: Vec::new(),
+            deep_verify: false,
+            check_empty_jar: false,
+            check_recorded_size: true,
+            check_missing_jar: false,
+            check_parent_pom: false,
+            check_version_mismatch: false,
+            check_manifest: false,
+            max_open_files: 256,
+            thread_count: None,
+            max_results: None,
+            max_files: DEFAULT_MAX_SCAN_FILES,
+            force: false,
+            debug_log_path: None,
+            validator_cmd: None,
+            validator_concurrency: 4,
+            validator_timeout_secs: 60,
         }
     }
+}
 
-    // 3. 第三优先级：用户级别 settings.xml (~/.m2/settings.xml)
-    println!("[步骤 3] 尝试读取用户级配置 ~/.m2/settings.xml");
-    if let Some(home_dir) = dirs::home_dir() {
-        println!("[DEBUG] 用户主目录: {}", home_dir.display());
-        let user_settings = home_dir.join(".m2").join("settings.xml");
-        if let Some(repo) = parse_local_repo(&user_settings) {
-            println!("========== ✅ 检测成功，返回路径: {} ==========\n", repo);
-            return Ok(repo);
+// ===================== 应用状态 =====================
+
+/// 由 Tauri 托管的全局状态
+#[derive(Default)]
+pub struct AppState {
+    /// 用户手动指定的仓库路径，设置后跳过自动检测
+    repo_path_override: Mutex<Option<String>>,
+    /// 最近一次完成的扫描结果，前端重新加载后可直接取回，无需重新扫描
+    last_scan: Mutex<Option<ScanReport>>,
+    /// 正在运行的仓库监视，丢弃即停止
+    repo_watch: Mutex<Option<notify::RecommendedWatcher>>,
+    /// 扫描的暂停开关，由扫描线程共享
+    scan_pause: Arc<ScanPause>,
+}
+
+// ===================== 常量配置 =====================
+
+const BAD_POM_KEYWORDS: &[&str] = &[
+    "<!DOCTYPE html>",
+    "<title>Harbor</title>",
+    "Login to Harbor",
+];
+const NEXUS_POM_KEYWORDS: &[&str] = &["<!-- Nexus NX-", "<title>Nexus Repository Manager</title>"];
+const ARTIFACTORY_KEYWORDS: &[&str] = &["<title>JFrog Artifactory</title>", "<address>Artifactory/"];
+const HARBOR_KEYWORD_GROUP: KeywordGroup = KeywordGroup {
+    keywords: BAD_POM_KEYWORDS,
+    json_errors: false,
+    reason: Reason::HarborPom,
+};
+const NEXUS_KEYWORD_GROUP: KeywordGroup = KeywordGroup {
+    keywords: NEXUS_POM_KEYWORDS,
+    json_errors: false,
+    reason: Reason::NexusError,
+};
+const ARTIFACTORY_KEYWORD_GROUP: KeywordGroup = KeywordGroup {
+    keywords: ARTIFACTORY_KEYWORDS,
+    json_errors: true,
+    reason: Reason::ArtifactoryError,
+};
+const METADATA_FILES: &[&str] = &[
+    "_remote.repositories",
+    "_maven.repositories",
+    "resolver-status.properties",
+];
+/// 下载出错时可能被写进文件开头的 HTTP 响应头字段（小写）
+const HTTP_HEADER_NAMES: &[&str] = &[
+    "content-type",
+    "content-length",
+    "server",
+    "date",
+    "transfer-encoding",
+    "connection",
+];
+/// gzip 数据的魔数
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+/// 检测 HTTP 响应头时读取的文件头部字节数
+const HTTP_PREAMBLE_PREVIEW_BYTES: usize = 256;
+/// 中断下载遗留的临时文件扩展名，总是可以安全删除
+const TEMP_DOWNLOAD_EXTENSIONS: &[&str] = &[".part", ".tmp", ".in_progress"];
+/// 校验和文件使用的摘要算法扩展名
+const CHECKSUM_ALGORITHMS: &[&str] = &["md5", "sha1", "sha256", "sha512"];
+/// ZIP 格式的构件扩展名，按 JAR 的规则检查（ZIP 结构、大小等）
+const ARCHIVE_EXTENSIONS: &[&str] = &["jar", "war", "ear", "aar", "zip"];
+/// 文本描述文件的扩展名，按 POM 的规则检查错误页面关键词
+const DESCRIPTOR_EXTENSIONS: &[&str] = &["pom", "module", "xml"];
+/// 新建 settings.xml 时使用的最小骨架，`{}` 处填入仓库路径
+const SETTINGS_SKELETON: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<settings xmlns="http://maven.apache.org/SETTINGS/1.0.0"
+          xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+          xsi:schemaLocation="http://maven.apache.org/SETTINGS/1.0.0 https://maven.apache.org/xsd/settings-1.0.0.xsd">
+  <localRepository>{}</localRepository>
+</settings>
+"#;
+/// 只含一个空条目的最小 ZIP 约为 100 字节，更小的 JAR 不可能包含任何内容
+const QUICK_MIN_JAR_BYTES: u64 = 100;
+/// 修改时间允许超前当前时间的最大幅度
+const MTIME_FUTURE_TOLERANCE: Duration = Duration::from_secs(24 * 60 * 60);
+/// 远程校验时的最大并发请求数
+const REMOTE_VERIFY_CONCURRENCY: usize = 8;
+/// 远程校验单个请求的超时时间
+const REMOTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// 删除重试的基础等待时间，第 n 次重试前等待 n 倍
+const DELETE_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+/// 扫描结束时发送汇总信息的事件名
+const SCAN_COMPLETE_EVENT: &str = "scan-complete";
+/// 扫描暂停或恢复时发送的事件名，负载为是否处于暂停状态
+const SCAN_PAUSE_EVENT: &str = "scan-pause-changed";
+/// 监视模式下发现损坏构件时发送的事件名
+const INVALID_FOUND_EVENT: &str = "invalid-found";
+/// 文件在该时间内没有新的写入事件即视为写入完成
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(2);
+/// 持续写入的文件最多等待该时间后也会检查，避免一直推迟
+const WATCH_MAX_DELAY: Duration = Duration::from_secs(30);
+/// 监视线程检查待处理文件的间隔
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 扫描线程数上限
+const MAX_SCAN_THREADS: usize = 256;
+/// 默认的扫描文件数上限，正常的本地仓库远小于该值
+const DEFAULT_MAX_SCAN_FILES: usize = 2_000_000;
+/// 仓库路径存在但不是目录时错误信息的前缀，前端据此与其他失败区分
+const NOT_A_DIRECTORY_PREFIX: &str = "NotADirectory";
+/// 安全检查拒绝扫描时错误信息的前缀，前端据此提示用户确认后传入 `force: true`
+const SCAN_REFUSED_PREFIX: &str = "ScanRefused";
+/// `mvn -v` 的最长等待时间，超时视为命令执行失败
+const MVN_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ===================== 辅助函数 =====================
+
+thread_local! {
+    /// 当前线程收集的日志行，`None` 表示未在收集
+    static LOG_CAPTURE: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// 收集一行日志，当前线程未在收集时不做任何事
+fn capture_log_line(line: String) {
+    LOG_CAPTURE.with(|capture| {
+        if let Some(lines) = capture.borrow_mut().as_mut() {
+            lines.push(line);
         }
-    }
+    });
+}
 
-    // 4. 兜底：返回默认路径 ~/.m2/repository
-    println!("[步骤 4] 使用默认路径");
-    let home_dir = dirs::home_dir().ok_or("无法获取用户主目录")?;
-    let default_repo = home_dir.join(".m2").join("repository");
-    let default_path = default_repo.to_string_lossy().to_string();
-    println!("========== ⚠️ 使用默认路径: {} ==========\n", default_path);
-    Ok(default_path)
+/// 执行 `f` 并收集期间当前线程通过 `log_msg!` 输出的日志行
+///
+/// 只收集当前线程的日志，`f` 中派生的线程输出不会被收集
+fn with_log_capture<T>(f: impl FnOnce() -> T) -> (T, Vec<String>) {
+    let previous = LOG_CAPTURE.with(|capture| capture.replace(Some(Vec::new())));
+    let result = f();
+    let lines = LOG_CAPTURE
+        .with(|capture| capture.replace(previous))
+        .unwrap_or_default();
+    (result, lines)
 }
 
-#[tauri::command]
-fn scan_invalid_artifacts(repo_path: String) -> Result<Vec<InvalidArtifact>, String> {
-    let repo_path = Path::new(&repo_path);
+/// `MVNCLEAN_LANG` 以 `en` 开头时输出英文日志，否则输出中文
+fn use_english_logs() -> bool {
+    static ENGLISH: OnceLock<bool> = OnceLock::new();
+    *ENGLISH.get_or_init(|| {
+        std::env::var("MVNCLEAN_LANG")
+            .map(|v| v.to_lowercase().starts_with("en"))
+            .unwrap_or(false)
+    })
+}
 
-    if !repo_path.exists() {
-        return Err(format!("仓库路径不存在: {}", repo_path.display()));
+/// 按语言选出 `log_msg!` 输出的一行日志，只格式化选中的一种
+fn log_line(zh: impl FnOnce() -> String, en: impl FnOnce() -> String, english: bool) -> String {
+    if english {
+        en()
+    } else {
+        zh()
     }
+}
 
-    if !repo_path.is_dir() {
-        return Err(format!("路径不是目录: {}", repo_path.display()));
+/// Windows 下将路径转换为 `\\?\` 扩展长度形式，绕过 MAX_PATH (260 字符) 限制
+#[cfg(target_os = "windows")]
+fn to_long_path(path: &Path) -> PathBuf {
+    if path.to_string_lossy().starts_with(r"\\?\") {
+        return path.to_path_buf();
     }
 
-    // 根据 CPU 核心数配置线程池 (IO 密集型,设为核心数 * 4)
-    let cpu_count = num_cpus::get();
-    let thread_count = cpu_count * 4;
+    // 扩展长度路径不会再做规范化，必须先转换为绝对路径
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let raw = absolute.to_string_lossy().replace('/', "\\");
+    match raw.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", raw)),
+    }
+}
 
-    println!("[多线程扫描] CPU 核心数: {}, 线程池大小: {}", cpu_count, thread_count);
+#[cfg(not(target_os = "windows"))]
+fn to_long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
 
-    // 配置 Rayon 全局线程池
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(thread_count)
-        .build_global()
-        .ok(); // 忽略重复初始化错误
+/// 去掉 `\\?\` 前缀，返回适合展示给用户的路径字符串
+fn display_path(path: &Path) -> String {
+    let raw = path.to_string_lossy();
+    if let Some(unc) = raw.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", unc)
+    } else if let Some(rest) = raw.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        raw.to_string()
+    }
+}
+
+/// 确认仓库路径存在且是目录；路径指向文件时返回"不是目录"而非"不存在"
+fn ensure_repo_dir(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("仓库路径不存在: {}", path.display()));
+    }
+    if !path.is_dir() {
+        return Err(format!(
+            "{}: 路径不是目录: {}",
+            NOT_A_DIRECTORY_PREFIX,
+            path.display()
+        ));
+    }
+    Ok(())
+}
 
-    // 第一阶段：收集所有待检查的文件路径
-    let files_to_check: Vec<_> = WalkDir::new(repo_path)
+/// 规范化仓库路径（解析 `.`、`..`、多余的分隔符和符号链接），使扫描输出的目录
+/// 与 `clean_artifacts` 规范化后的路径一致；失败时退回原路径
+fn canonical_repo_path(path: &Path) -> PathBuf {
+    match fs::canonicalize(to_long_path(path)) {
+        Ok(canonical) => canonical,
+        Err(e) => {
+            log_msg!(
+                "[路径规范化] 无法规范化 {}，使用原路径: {}",
+                "[Canonicalize] Cannot canonicalize {}, using it as is: {}",
+                path.display(),
+                e
+            );
+            path.to_path_buf()
+        }
+    }
+}
+
+/// 遍历仓库目录下的所有文件（跳过隐藏目录）
+fn walk_repo_files(repo_path: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(to_long_path(repo_path))
         .into_iter()
         .filter_entry(|e| {
             // 跳过隐藏目录
-            !e.file_name()
-                .to_str()
-                .map(|s| s.starts_with('.'))
-                .unwrap_or(false)
+            !(e.file_type().is_dir()
+                && e.depth() > 0
+                && e.file_name()
+                    .to_str()
+                    .map(|s| s.starts_with('.'))
+                    .unwrap_or(false))
         })
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_file())
-        .filter(|e| {
-            // 只处理 .jar 和 .pom 文件
-            e.path()
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| ext == "jar" || ext == "pom")
-                .unwrap_or(false)
-        })
-        .map(|e| e.path().to_path_buf())
-        .collect();
-
-    println!("[多线程扫描] 发现 {} 个 JAR/POM 文件,开始并行检查...", files_to_check.len());
+}
 
-    // 第二阶段：并行检查所有文件
-    let invalid_artifacts: Vec<InvalidArtifact> = files_to_check
-        .par_iter() // 使用 Rayon 并行迭代器
-        .filter_map(|path| {
-            let file_name = path.file_name()?.to_str()?;
-            let mut is_bad = false;
-            let mut reason = String::new();
-
-            // 检查损坏的 JAR（验证 ZIP 格式完整性）
-            if file_name.ends_with(".jar") {
-                // 尝试作为 ZIP 打开
-                match fs::File::open(path) {
-                    Ok(file) => {
-                        match ZipArchive::new(file) {
-                            Ok(_) => {
-                                // ZIP 格式正常，不是损坏文件
-                            }
-                            Err(e) => {
-                                is_bad = true;
-                                // 记录详细错误原因
-                                reason = format!("ZIP 格式损坏: {}", e);
-                                println!("[ZIP 验证失败] {}: {}", path.display(), e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        is_bad = true;
-                        reason = format!("无法读取文件: {}", e);
-                    }
-                }
-            }
-            // 检查损坏的 POM
-            else if file_name.ends_with(".pom") {
-                if let Ok(content) = fs::read_to_string(path) {
-                    let preview = &content.chars().take(1024).collect::<String>();
-                    for keyword in BAD_POM_KEYWORDS {
-                        if preview.contains(keyword) {
-                            is_bad = true;
-                            reason = "包含错误页面的POM文件".to_string();
-                            break;
-                        }
-                    }
-                }
-            }
+/// 遍历仓库目录，收集文件名满足 `accept` 的文件（跳过隐藏目录）
+fn collect_repo_files<F>(repo_path: &Path, accept: F) -> Vec<PathBuf>
+where
+    F: Fn(&str) -> bool,
+{
+    walk_repo_files(repo_path)
+        .filter(|e| e.file_name().to_str().map(&accept).unwrap_or(false))
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
 
-            if is_bad {
-                let parent = path.parent()?;
-                let base_name = file_name
-                    .trim_end_matches(".jar")
-                    .trim_end_matches(".pom")
-                    .to_string();
+/// 与 `collect_repo_files` 相同，但遍历到的文件总数超过 `max_files` 时立即停止并拒绝扫描
+fn collect_repo_files_guarded<F>(
+    repo_path: &Path,
+    max_files: usize,
+    accept: F,
+) -> Result<Vec<PathBuf>, String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut files = Vec::new();
+    for (seen, entry) in walk_repo_files(repo_path).enumerate() {
+        if seen >= max_files {
+            return Err(format!(
+                "{}: 目录中的文件超过 {} 个，可能不是 Maven 仓库: {}；确认无误后传入 force: true 重新扫描",
+                SCAN_REFUSED_PREFIX,
+                max_files,
+                repo_path.display()
+            ));
+        }
+        if entry.file_name().to_str().is_some_and(&accept) {
+            files.push(entry.path().to_path_buf());
+        }
+    }
+    Ok(files)
+}
 
-                Some(InvalidArtifact {
-                    folder: parent.to_string_lossy().to_string(),
-                    base_name,
-                    reason,
-                })
+/// 路径中是否有名为 `.m2` 或 `repository` 的目录（Windows 下不区分大小写），
+/// 用于判断扫描目标是否像 Maven 仓库
+fn looks_like_repo_path(path: &Path) -> bool {
+    path.components().any(|c| {
+        let name = c.as_os_str().to_string_lossy();
+        [".m2", "repository"].iter().any(|expected| {
+            if cfg!(windows) {
+                name.eq_ignore_ascii_case(expected)
             } else {
-                None
+                name == *expected
             }
         })
-        .collect();
+    })
+}
 
-    println!("[多线程扫描] 扫描完成,发现 {} 个损坏的构件", invalid_artifacts.len());
+/// 判断文件名是否为指定类型之一的构件
+fn has_artifact_extension(file_name: &str, types: &[ArtifactType]) -> bool {
+    Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| types.iter().any(|t| t.extension() == ext))
+        .unwrap_or(false)
+}
 
-    Ok(invalid_artifacts)
+/// 判断文件名是否为配置中的构件：扩展名在 `artifact_extensions` 中，
+/// 且 JAR / POM 未被 `artifact_types` 排除
+fn is_configured_artifact(file_name: &str, config: &ScanConfig) -> bool {
+    let ext = match file_extension(file_name) {
+        Some(ext) => ext,
+        None => return false,
+    };
+    if !config.artifact_extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+        return false;
+    }
+    match ArtifactType::ALL.iter().find(|t| t.extension() == ext) {
+        Some(t) => config.artifact_types.contains(t),
+        None => true,
+    }
 }
 
-#[tauri::command]
-fn clean_artifacts(items: Vec<CleanItem>) -> Result<CleanResult, String> {
-    let mut deleted_count = 0;
-    let mut errors = Vec::new();
+/// 取文件扩展名（不含点）
+fn file_extension(file_name: &str) -> Option<&str> {
+    Path::new(file_name).extension().and_then(|ext| ext.to_str())
+}
 
-    for item in items {
-        let folder = Path::new(&item.folder);
+/// 判断是否为 ZIP 格式的构件（JAR、WAR、AAR 等）
+fn is_archive_file(file_name: &str) -> bool {
+    file_extension(file_name).is_some_and(|ext| ARCHIVE_EXTENSIONS.contains(&ext))
+}
 
-        if !folder.exists() {
-            continue;
-        }
+/// 判断是否为文本描述文件（POM、Gradle `.module` 等）
+fn is_descriptor_file(file_name: &str) -> bool {
+    file_extension(file_name).is_some_and(|ext| DESCRIPTOR_EXTENSIONS.contains(&ext))
+}
 
-        let entries = match fs::read_dir(folder) {
-            Ok(entries) => entries,
-            Err(e) => {
-                errors.push(format!("无法读取目录 {}: {}", folder.display(), e));
-                continue;
-            }
-        };
+/// 遍历仓库目录，收集指定类型的构件文件（跳过隐藏目录）
+fn collect_artifact_files(repo_path: &Path, types: &[ArtifactType]) -> Vec<PathBuf> {
+    collect_repo_files(repo_path, |name| has_artifact_extension(name, types))
+}
 
-        for entry in entries.flatten() {
-            let file_path = entry.path();
-            let file_name = match file_path.file_name().and_then(|n| n.to_str()) {
-                Some(name) => name,
-                None => continue,
+/// 若 `folder` 是 `base_name` 所属的版本目录且只包含该构件的文件，返回其中的
+/// (构件文件数, 元数据文件数)
+///
+/// 版本目录形如 `<artifactId>/<version>/`，其中文件都应以 `<artifactId>-` 开头；
+/// 只要存在子目录或其他构件的文件就返回 `None`，避免误删无关内容。
+fn version_dir_file_count(folder: &Path, base_name: &str) -> Option<(usize, usize)> {
+    let version = folder.file_name()?.to_str()?;
+    let artifact_id = folder.parent()?.file_name()?.to_str()?;
+    if !base_name.starts_with(&format!("{}-{}", artifact_id, version)) {
+        return None;
+    }
+
+    let prefix = format!("{}-", artifact_id);
+    let mut artifact_count = 0;
+    let mut metadata_count = 0;
+    for entry in fs::read_dir(folder).ok()? {
+        let entry = entry.ok()?;
+        if !entry.file_type().ok()?.is_file() {
+            return None;
+        }
+        let name = entry.file_name();
+        let name = name.to_str()?;
+        if METADATA_FILES.contains(&name) {
+            metadata_count += 1;
+        } else if name.starts_with(&prefix) {
+            artifact_count += 1;
+        } else {
+            return None;
+        }
+    }
+    Some((artifact_count, metadata_count))
+}
+
+/// 判断是否为 Maven 或插件中断后遗留的锁文件
+fn is_lock_file(file_name: &str) -> bool {
+    file_name.ends_with(".lock") || file_name.starts_with(".nfs")
+}
+
+/// 判断清理条目的 `base_name` 是否为构件基础名
+///
+/// 锁文件、下载临时文件、校验和文件和 maven-metadata 以完整文件名作为 `base_name`，
+/// 清理时只删除该文件本身，目录中的元数据文件仍属于同目录的构件，不能连带删除
+fn is_artifact_base_name(base_name: &str) -> bool {
+    !(is_lock_file(base_name)
+        || is_temp_download_file(base_name)
+        || checksum_sidecar(base_name).is_some()
+        || is_maven_metadata_file(base_name))
+}
+
+/// 解析 `_remote.repositories`，返回 (文件名, 仓库 id) 列表
+///
+/// 每行格式为 `<文件名>><仓库 id>=`，`#` 开头为注释；仓库 id 为空表示本地安装
+fn parse_remote_repositories(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (file_name, rest) = line.split_once('>')?;
+            let remote_id = rest.split('=').next().unwrap_or_default().trim();
+            let remote_id = if remote_id.is_empty() {
+                "(local)"
+            } else {
+                remote_id
             };
+            Some((file_name.trim().to_string(), remote_id.to_string()))
+        })
+        .collect()
+}
 
-            let should_delete = file_name.starts_with(&item.base_name)
-                || METADATA_FILES.contains(&file_name);
+/// 读取 `_remote.repositories` 中记录的文件预期大小
+///
+/// 标准格式 `=` 之后为空，部分代理会写成 `<文件名>><仓库 id>=<字节数>`；
+/// 只收集能解析为数字的记录
+fn parse_recorded_sizes(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (file_name, rest) = line.split_once('>')?;
+            let (_, size) = rest.split_once('=')?;
+            let size = size.trim().parse().ok()?;
+            Some((file_name.trim().to_string(), size))
+        })
+        .collect()
+}
 
-            if should_delete {
-                match fs::remove_file(&file_path) {
-                    Ok(_) => deleted_count += 1,
-                    Err(e) => {
-                        errors.push(format!("删除失败 {}: {}", file_path.display(), e));
-                    }
+/// 与 `_remote.repositories` 中记录的预期大小比对，不一致时返回原因
+fn check_recorded_size(path: &Path, file_name: &str) -> Option<Finding> {
+    let content = fs::read_to_string(path.with_file_name("_remote.repositories")).ok()?;
+    let expected = *parse_recorded_sizes(&content).get(file_name)?;
+    let actual = fs::metadata(path).ok()?.len();
+    if actual == expected {
+        return None;
+    }
+    Some(Finding::with_detail(
+        Reason::SizeMismatch,
+        format!("实际 {} 字节，预期 {} 字节", actual, expected),
+    ))
+}
+
+/// 判断是否为中断下载遗留的临时文件
+fn is_temp_download_file(file_name: &str) -> bool {
+    TEMP_DOWNLOAD_EXTENSIONS
+        .iter()
+        .any(|ext| file_name.ends_with(ext))
+}
+
+/// 解析校验和文件名，返回 (对应的构件文件名, 算法)
+///
+/// 除标准的 `<构件>.<算法>` 外，也识别同步出错产生的 `<构件>.<算法>.<算法>`
+/// 等形式，构件名取第一次出现 `.<算法>` 之前的部分（不区分大小写）
+fn checksum_sidecar(file_name: &str) -> Option<(&str, &'static str)> {
+    let lower = file_name.to_ascii_lowercase();
+    let algorithm = CHECKSUM_ALGORITHMS
+        .iter()
+        .copied()
+        .find(|algo| lower.ends_with(&format!(".{}", algo)))?;
+    let ext = format!(".{}", algorithm);
+    let artifact_len = lower
+        .match_indices(&ext)
+        .map(|(i, _)| i)
+        .find(|&i| matches!(lower.as_bytes().get(i + ext.len()), None | Some(b'.')))?;
+    if artifact_len == 0 {
+        return None;
+    }
+    Some((&file_name[..artifact_len], algorithm))
+}
+
+/// 读取校验和文件中的摘要值（只取第一个字段，转为小写）
+fn read_checksum_value(path: &Path) -> Option<String> {
+    let content = read_preview(path, 1024).ok()?;
+    content
+        .split_whitespace()
+        .next()
+        .map(|value| value.to_ascii_lowercase())
+}
+
+/// 查找同一目录下同一构件存在多个同算法、但内容不一致的校验和文件
+fn find_checksum_conflicts(files: &[PathBuf], semaphore: &Semaphore) -> Vec<InvalidArtifact> {
+    let mut groups: HashMap<(&Path, &str, &str), Vec<&Path>> = HashMap::new();
+    for path in files {
+        let sidecar = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(checksum_sidecar);
+        if let (Some(folder), Some((artifact, algorithm))) = (path.parent(), sidecar) {
+            groups
+                .entry((folder, artifact, algorithm))
+                .or_default()
+                .push(path);
+        }
+    }
+
+    groups
+        .into_par_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .filter_map(|((folder, artifact, algorithm), paths)| {
+            let _permit = semaphore.acquire();
+            let mut values: Vec<String> =
+                paths.iter().filter_map(|p| read_checksum_value(p)).collect();
+            values.sort();
+            values.dedup();
+            if values.len() < 2 {
+                return None;
+            }
+            Some(InvalidArtifact::new(
+                display_path(folder),
+                artifact_base_name(artifact),
+                Finding::with_detail(
+                    Reason::ConflictingChecksums,
+                    format!("{} 个 .{} 文件内容不一致", paths.len(), algorithm),
+                ),
+            ))
+        })
+        .collect()
+}
+
+/// 判断是否为 `maven-metadata*.xml` 元数据文件
+fn is_maven_metadata_file(file_name: &str) -> bool {
+    file_name.starts_with("maven-metadata") && file_name.ends_with(".xml")
+}
+
+/// 元数据文件能被解析且根元素为 `<metadata>` 时视为有效
+fn is_valid_maven_metadata(path: &Path) -> bool {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+    match roxmltree::Document::parse(strip_leading_noise(&content)) {
+        Ok(doc) => doc.root_element().has_tag_name("metadata"),
+        Err(_) => false,
+    }
+}
+
+/// 判断文件最近 `min_age_secs` 秒内是否未被修改（无法获取时间时视为可清理）
+fn is_older_than(path: &Path, min_age_secs: u64) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .map(|age| age.as_secs() >= min_age_secs)
+        .unwrap_or(true)
+}
+
+/// 由文件名计算构件基础名：只去掉最后一个构件扩展名，如 `a.pom.jar` 得到 `a.pom`
+fn artifact_base_name(file_name: &str) -> String {
+    match file_name.rsplit_once('.') {
+        Some((base, _)) if is_archive_file(file_name) || is_descriptor_file(file_name) => {
+            base.to_string()
+        }
+        _ => file_name.to_string(),
+    }
+}
+
+/// 判断文件是否属于 `base_name` 对应的构件：文件名等于基础名或为 `<基础名>.<扩展名>`
+///
+/// 不按前缀匹配，避免清理 `foo-1.0` 时连带删除 `foo-1.0-sources.jar` 等其他构件
+fn belongs_to_artifact(file_name: &str, base_name: &str) -> bool {
+    file_name
+        .strip_prefix(base_name)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// 流式计算文件的摘要（小写十六进制）
+fn compute_digest<D: sha1::Digest + Write>(path: &Path) -> Result<String, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    digest_reader::<D>(file)
+}
+
+/// 流式计算读取内容的摘要（小写十六进制）
+fn digest_reader<D: sha1::Digest + Write>(mut reader: impl Read) -> Result<String, String> {
+    let mut hasher = D::new();
+    std::io::copy(&mut reader, &mut hasher).map_err(|e| e.to_string())?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// 流式计算文件的 SHA-1 摘要（小写十六进制）
+fn compute_sha1(path: &Path) -> Result<String, String> {
+    compute_digest::<sha1::Sha1>(path)
+}
+
+/// 按校验和文件的算法扩展名计算文件摘要
+fn compute_checksum(path: &Path, algorithm: &str) -> Result<String, String> {
+    match algorithm {
+        "md5" => compute_digest::<md5::Md5>(path),
+        "sha1" => compute_sha1(path),
+        "sha256" => compute_digest::<sha2::Sha256>(path),
+        "sha512" => compute_digest::<sha2::Sha512>(path),
+        _ => Err(format!("不支持的校验和算法: {}", algorithm)),
+    }
+}
+
+/// 处理零字节的校验和文件：开启重新生成时按相邻构件计算摘要并写入，否则报告
+///
+/// 快速模式不读取文件内容，只报告不重新生成
+fn check_empty_checksum(path: &Path, file_name: &str, config: &ScanConfig) -> Option<Finding> {
+    let (artifact, algorithm) = checksum_sidecar(file_name)?;
+    if fs::metadata(path).ok()?.len() != 0 {
+        return None;
+    }
+
+    let artifact_path = path.with_file_name(artifact);
+    if config.regenerate_empty_checksums && !config.quick && artifact_path.is_file() {
+        match compute_checksum(&artifact_path, algorithm).and_then(|digest| {
+            fs::write(path, digest).map_err(|e| e.to_string())
+        }) {
+            Ok(_) => {
+                log_msg!(
+                    "[空校验和] 已重新生成: {}",
+                    "[Empty checksum] Regenerated: {}",
+                    display_path(path)
+                );
+                return None;
+            }
+            Err(e) => return Some(Finding::with_detail(Reason::EmptyChecksum, e)),
+        }
+    }
+    Some(Finding::new(Reason::EmptyChecksum))
+}
+
+/// 单个构件的远程校验结果
+enum RemoteCheck {
+    Match,
+    Mismatch,
+    NotFound,
+    Failed(String),
+}
+
+/// 下载远程 .sha1 文件并与本地摘要比对
+fn check_against_remote(
+    client: &reqwest::blocking::Client,
+    base_url: &str,
+    repo_path: &Path,
+    path: &Path,
+) -> RemoteCheck {
+    let relative = match path.strip_prefix(repo_path) {
+        Ok(r) => r,
+        Err(e) => return RemoteCheck::Failed(format!("{}: {}", path.display(), e)),
+    };
+    let relative_url = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let url = format!("{}/{}.sha1", base_url.trim_end_matches('/'), relative_url);
+
+    let response = match client.get(&url).send() {
+        Ok(r) => r,
+        Err(e) => return RemoteCheck::Failed(format!("请求失败 {}: {}", url, e)),
+    };
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return RemoteCheck::NotFound;
+    }
+    if !response.status().is_success() {
+        return RemoteCheck::Failed(format!("请求失败 {}: HTTP {}", url, response.status()));
+    }
+
+    let body = match response.text() {
+        Ok(b) => b,
+        Err(e) => return RemoteCheck::Failed(format!("读取响应失败 {}: {}", url, e)),
+    };
+    // .sha1 文件可能是 "<hash>  <filename>" 格式，只取第一个字段
+    let remote_sha1 = match body.split_whitespace().next() {
+        Some(h) => h.to_lowercase(),
+        None => return RemoteCheck::Failed(format!("远程校验和为空: {}", url)),
+    };
+
+    match compute_sha1(path) {
+        Ok(local_sha1) if local_sha1 == remote_sha1 => RemoteCheck::Match,
+        Ok(_) => RemoteCheck::Mismatch,
+        Err(e) => RemoteCheck::Failed(format!("无法计算校验和 {}: {}", path.display(), e)),
+    }
+}
+
+/// 验证 JAR 的 ZIP 结构并执行已开启的内容检查，损坏时返回原因
+///
+/// `artifact_id` 为从仓库路径推导出的坐标，未知时跳过与坐标相关的检查
+fn check_jar<R: Read + Seek>(
+    reader: R,
+    artifact_id: Option<&str>,
+    config: &ScanConfig,
+) -> Option<Finding> {
+    record_check("zip");
+    let mut archive = match ZipArchive::new(reader) {
+        Ok(archive) => archive,
+        Err(e) => return Some(Finding::with_detail(Reason::CorruptZip, e)),
+    };
+
+    if config.deep_verify {
+        record_check("deep_verify");
+        if let Some(finding) = verify_entry_crcs(&mut archive) {
+            return Some(finding);
+        }
+    }
+
+    if config.check_empty_jar {
+        record_check("empty_jar");
+        if is_empty_jar(&archive) {
+            return Some(Finding::new(Reason::EmptyJar));
+        }
+    }
+
+    if config.check_manifest {
+        if let Some(artifact_id) = artifact_id {
+            record_check("manifest");
+            if manifest_mismatches(&mut archive, artifact_id) {
+                return Some(Finding::new(Reason::ManifestMismatch));
+            }
+        }
+    }
+
+    None
+}
+
+/// 除 `META-INF/` 下的条目（清单、签名等）外没有任何文件时视为空 JAR
+fn is_empty_jar<R: Read + Seek>(archive: &ZipArchive<R>) -> bool {
+    archive
+        .file_names()
+        .all(|name| name.starts_with("META-INF/") || name.ends_with('/'))
+}
+
+/// 完整读取每个条目，由 `zip` 在读到末尾时校验 CRC，发现第一个损坏条目即返回
+fn verify_entry_crcs<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<Finding> {
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(e) => return Some(Finding::with_detail(Reason::EntryCrcMismatch, e)),
+        };
+        if let Err(e) = std::io::copy(&mut entry, &mut std::io::sink()) {
+            return Some(Finding::with_detail(
+                Reason::EntryCrcMismatch,
+                format!("{}: {}", entry.name(), e),
+            ));
+        }
+    }
+    None
+}
+
+/// 从仓库布局 `<artifactId>/<version>/<file>` 推导构件的 artifactId
+fn path_artifact_id(path: &Path) -> Option<&str> {
+    path.parent()?.parent()?.file_name()?.to_str()
+}
+
+/// 读取 MANIFEST.MF 中的主属性（处理以空格开头的续行）
+fn read_manifest_attributes<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+) -> Option<HashMap<String, String>> {
+    let mut entry = archive.by_name("META-INF/MANIFEST.MF").ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+
+    let mut attributes: HashMap<String, String> = HashMap::new();
+    let mut last_key: Option<String> = None;
+    for line in content.lines() {
+        // 空行之后是各条目的属性段，只关心主属性
+        if line.is_empty() {
+            break;
+        }
+        if let Some(continuation) = line.strip_prefix(' ') {
+            if let Some(value) = last_key.as_ref().and_then(|k| attributes.get_mut(k)) {
+                value.push_str(continuation);
+            }
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let key = key.trim().to_string();
+            attributes.insert(key.clone(), value.trim().to_string());
+            last_key = Some(key);
+        }
+    }
+    Some(attributes)
+}
+
+/// 只保留字母和数字并转为小写，便于宽松比较
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// 判断 MANIFEST 声明的名称是否与 artifactId 明显不符
+///
+/// 为避免误报，只有在名称与 artifactId 完全没有重叠（互不包含，且 artifactId
+/// 中长度不少于 3 的片段都没有出现在名称中）时才认为不符。
+fn manifest_mismatches<R: Read + Seek>(archive: &mut ZipArchive<R>, artifact_id: &str) -> bool {
+    let attributes = match read_manifest_attributes(archive) {
+        Some(attributes) => attributes,
+        None => return false,
+    };
+
+    let declared: Vec<String> = ["Implementation-Title", "Bundle-SymbolicName"]
+        .iter()
+        .filter_map(|key| attributes.get(*key))
+        // Bundle-SymbolicName 可能带有 `;singleton:=true` 等指令
+        .map(|value| value.split(';').next().unwrap_or_default())
+        // 未替换的占位符无法比较
+        .filter(|value| !value.is_empty() && !value.contains("${"))
+        .map(normalize_name)
+        .filter(|value| !value.is_empty())
+        .collect();
+
+    if declared.is_empty() {
+        return false;
+    }
+
+    let expected = normalize_name(artifact_id);
+    let tokens: Vec<String> = artifact_id
+        .split(['-', '_', '.'])
+        .map(normalize_name)
+        .filter(|t| t.len() >= 3)
+        .collect();
+
+    declared.iter().all(|name| {
+        !name.contains(&expected)
+            && !expected.contains(name.as_str())
+            && !tokens.iter().any(|t| name.contains(t.as_str()))
+    })
+}
+
+/// 判断内容是否为 Artifactory 风格的 JSON 错误响应，如
+/// `{"errors" : [ {"status" : 404, "message" : "..."} ]}`
+///
+/// 忽略空白后只比较开头结构，文件头部被截断时也能识别
+fn has_json_error_shape(preview: &str) -> bool {
+    let compact: String = preview.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.starts_with(r#"{"errors":[{"status":"#) && compact.contains(r#""message":"#)
+}
+
+/// 去掉开头的 UTF-8 BOM 和空白（部分代理保存的文件会带上），避免干扰前缀匹配和 XML 解析
+fn strip_leading_noise(content: &str) -> &str {
+    content.trim_start_matches('\u{feff}').trim_start()
+}
+
+impl KeywordGroup {
+    fn matches(&self, preview: &str) -> bool {
+        let preview = strip_leading_noise(preview);
+        self.keywords.iter().any(|k| preview.contains(k))
+            || (self.json_errors && has_json_error_shape(preview))
+    }
+}
+
+/// 在 POM 文件头部查找错误页面关键词，命中时返回原因
+///
+/// 私服专属的关键词组优先匹配，以便与通用 HTML 页面区分开
+fn check_pom_preview(preview: &str, config: &ScanConfig) -> Option<Finding> {
+    let groups = [
+        (config.artifactory_keywords, "artifactory_keywords", &ARTIFACTORY_KEYWORD_GROUP),
+        (config.nexus_keywords, "nexus_keywords", &NEXUS_KEYWORD_GROUP),
+        (config.harbor_keywords, "harbor_keywords", &HARBOR_KEYWORD_GROUP),
+    ];
+
+    groups
+        .iter()
+        .filter(|(enabled, _, _)| *enabled)
+        .find(|(_, name, group)| {
+            record_check(*name);
+            group.matches(preview)
+        })
+        .map(|(_, _, group)| Finding::new(group.reason))
+}
+
+/// 检测的输入来源：内存中的内容（归档扫描等）或磁盘上的文件
+#[derive(Clone, Copy)]
+pub enum ArtifactInput<'a> {
+    Bytes(&'a [u8]),
+    File(&'a Path),
+}
+
+/// 读取 POM 的 `<packaging>`，未声明时为默认的 `jar`；无法解析时返回 `None`
+fn read_pom_packaging(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let doc = roxmltree::Document::parse(strip_leading_noise(&content)).ok()?;
+    let packaging = doc
+        .root_element()
+        .children()
+        .find(|n| n.is_element() && n.has_tag_name("packaging"))
+        .and_then(|n| n.text())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "jar".to_string());
+    Some(packaging)
+}
+
+/// 读取 POM `<parent>` 中的 (groupId, artifactId, version)，没有父 POM 或无法解析时返回 `None`
+fn read_pom_parent(path: &Path) -> Option<(String, String, String)> {
+    let content = fs::read_to_string(path).ok()?;
+    let doc = roxmltree::Document::parse(strip_leading_noise(&content)).ok()?;
+    let parent = doc
+        .root_element()
+        .children()
+        .find(|n| n.is_element() && n.has_tag_name("parent"))?;
+    let field = |name: &str| {
+        parent
+            .children()
+            .find(|n| n.is_element() && n.has_tag_name(name))
+            .and_then(|n| n.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    Some((field("groupId")?, field("artifactId")?, field("version")?))
+}
+
+/// 构件文件名中的版本与所在版本目录不符时返回原因，通常是复制文件时放错了目录
+///
+/// 仓库布局为 `<artifactId>/<version>/<artifactId>-<version>[-<classifier>].<ext>`；
+/// 文件名不以 `<artifactId>-` 开头时无法解析版本，跳过
+fn check_version_dir(path: &Path, file_name: &str) -> Option<Finding> {
+    let version = path.parent()?.file_name()?.to_str()?;
+    let artifact_id = path_artifact_id(path)?;
+    let base_name = artifact_base_name(file_name);
+    let rest = base_name.strip_prefix(artifact_id)?.strip_prefix('-')?;
+    if matches_version_dir(rest, version) {
+        return None;
+    }
+    Some(Finding::with_detail(
+        Reason::VersionMismatch,
+        format!("目录版本 {}", version),
+    ))
+}
+
+/// 判断文件名中 artifactId 之后的部分是否以目录版本开头（其后为 classifier 或结束）
+///
+/// `1.0-SNAPSHOT/` 目录下的文件也可能使用时间戳版本，如 `foo-1.0-20240101.120000-3.jar`
+fn matches_version_dir(rest: &str, version: &str) -> bool {
+    let ends_version = |s: &str| s.is_empty() || s.starts_with('-');
+    if rest.strip_prefix(version).is_some_and(ends_version) {
+        return true;
+    }
+    match version.strip_suffix("SNAPSHOT") {
+        Some(base) => rest
+            .strip_prefix(base)
+            .and_then(strip_snapshot_timestamp)
+            .is_some_and(ends_version),
+        None => false,
+    }
+}
+
+/// 去掉开头的快照时间戳 `yyyyMMdd.HHmmss-<构建号>`，不符合格式时返回 `None`
+fn strip_snapshot_timestamp(s: &str) -> Option<&str> {
+    let all_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let date = s.get(..8)?;
+    let rest = s.get(8..)?.strip_prefix('.')?;
+    let time = rest.get(..6)?;
+    let rest = rest.get(6..)?.strip_prefix('-')?;
+    let build_end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    if !all_digits(date) || !all_digits(time) || build_end == 0 {
+        return None;
+    }
+    Some(&rest[build_end..])
+}
+
+/// 父 POM 在仓库中按坐标推导出的路径下不存在时返回原因
+///
+/// 版本含属性占位符或版本范围时无法推导路径，跳过
+fn check_parent_pom(path: &Path, repo_root: &Path) -> Option<Finding> {
+    let (group_id, artifact_id, version) = read_pom_parent(path)?;
+    if version.contains("${") || version.starts_with(['[', '(']) {
+        return None;
+    }
+
+    let mut parent_path = repo_root.to_path_buf();
+    parent_path.extend(group_id.split('.'));
+    parent_path.push(&artifact_id);
+    parent_path.push(&version);
+    parent_path.push(format!("{}-{}.pom", artifact_id, version));
+    if parent_path.is_file() {
+        return None;
+    }
+    Some(Finding::with_detail(
+        Reason::MissingParentPom,
+        format!("{}:{}:{}", group_id, artifact_id, version),
+    ))
+}
+
+/// POM 声明为 jar 打包（或未声明）但同目录下没有同名的主 JAR
+///
+/// `pom` / `bom` 等打包方式本来就没有 JAR，不予报告
+fn is_missing_main_jar(path: &Path, file_name: &str) -> bool {
+    let jar_name = format!("{}.jar", artifact_base_name(file_name));
+    if path.with_file_name(&jar_name).exists() {
+        return false;
+    }
+    matches!(read_pom_packaging(path).as_deref(), Some("jar"))
+}
+
+/// 判断内容开头是否为原始 HTTP 响应（状态行或常见的响应头字段）
+///
+/// 与 HTML 错误页面不同，这类文件通常是下载工具把整个响应原样写入了磁盘
+fn has_http_preamble(preview: &str) -> bool {
+    let first_line = strip_leading_noise(preview).lines().next().unwrap_or_default();
+    if first_line.starts_with("HTTP/1.") || first_line.starts_with("HTTP/2") {
+        return true;
+    }
+    first_line
+        .split_once(':')
+        .map(|(name, _)| HTTP_HEADER_NAMES.contains(&name.trim().to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// 判断构件是否损坏，返回损坏原因；文件系统扫描与归档扫描共用此逻辑
+///
+/// `path` 为构件的位置：磁盘文件的路径，或条目在归档内的路径，用于按仓库布局解析 classifier
+fn classify_artifact(path: &Path, input: ArtifactInput, config: &ScanConfig) -> Option<Finding> {
+    let file_name = path.file_name()?.to_str()?;
+
+    // 快速模式不读取内容，只检查大小
+    if config.quick {
+        return check_jar_size(path, input, config);
+    }
+
+    // 文件开头混入了 HTTP 响应头：ZIP 允许前置数据，这类 JAR 仍可能被当作有效归档打开
+    if config.check_http_headers && (is_archive_file(file_name) || is_descriptor_file(file_name)) {
+        record_check("http_headers");
+        let preview = read_input_preview(input, HTTP_PREAMBLE_PREVIEW_BYTES);
+        if preview.is_some_and(|p| has_http_preamble(&p)) {
+            return Some(Finding::new(Reason::HttpHeaders));
+        }
+    }
+
+    // 检查损坏的 JAR / WAR 等归档（验证 ZIP 格式完整性）
+    if is_archive_file(file_name) {
+        let mut reason = match input {
+            ArtifactInput::Bytes(bytes) => check_jar(Cursor::new(bytes), None, config),
+            ArtifactInput::File(path) => match fs::File::open(path) {
+                Ok(file) => check_jar(file, path_artifact_id(path), config),
+                Err(e) => Some(Finding::with_detail(Reason::Unreadable, e)),
+            },
+        };
+        // gzip 数据或 Artifactory 的错误响应也会被保存为 JAR，此时给出更具体的原因
+        let is_corrupt_zip = matches!(&reason, Some(f) if f.reason == Reason::CorruptZip);
+        if is_corrupt_zip && starts_with_gzip_magic(input) {
+            reason = Some(Finding::new(Reason::GzipJar));
+        } else if is_corrupt_zip && config.artifactory_keywords {
+            record_check("artifactory_keywords");
+            let preview = read_input_preview(input, config.pom_preview_bytes);
+            if preview.is_some_and(|p| ARTIFACTORY_KEYWORD_GROUP.matches(&p)) {
+                reason = Some(Finding::new(Reason::ArtifactoryError));
+            }
+        }
+        // 能打开的截断文件：与代理记录的预期大小比对
+        if reason.is_none() && config.check_recorded_size {
+            if let ArtifactInput::File(path) = input {
+                record_check("recorded_size");
+                reason = check_recorded_size(path, file_name);
+            }
+        }
+        // 结构完好但过小的 JAR：按 classifier 的大小下限检查
+        if reason.is_none() {
+            reason = check_jar_size(path, input, config);
+        }
+        if let Some(finding) = reason {
+            log_msg!(
+                "[JAR 检查失败] {}: {}",
+                "[JAR check failed] {}: {}",
+                file_name,
+                finding.message
+            );
+            return Some(finding);
+        }
+    }
+    // 检查损坏的 POM / .module 等描述文件
+    else if is_descriptor_file(file_name) {
+        let preview = read_input_preview(input, config.pom_preview_bytes);
+        if let Some(finding) = preview.and_then(|p| check_pom_preview(&p, config)) {
+            return Some(finding);
+        }
+
+        if let ArtifactInput::File(path) = input {
+            let is_pom = file_name.ends_with(".pom");
+            if is_pom && config.check_missing_jar {
+                record_check("missing_jar");
+                if is_missing_main_jar(path, file_name) {
+                    return Some(Finding::new(Reason::MissingMainJar));
                 }
             }
         }
     }
 
-    Ok(CleanResult {
-        deleted_count,
-        errors,
+    // 检查异常的修改时间（仅磁盘文件）
+    if let ArtifactInput::File(path) = input {
+        if config.check_mtime {
+            record_check("mtime");
+            if has_abnormal_mtime(path, config.mtime_floor_year) {
+                return Some(Finding::new(Reason::AbnormalMtime));
+            }
+        }
+    }
+
+    None
+}
+
+/// 读取检测输入开头最多 `max_bytes` 字节，按 UTF-8 宽松解码
+fn read_input_preview(input: ArtifactInput, max_bytes: usize) -> Option<String> {
+    match input {
+        ArtifactInput::Bytes(bytes) => {
+            let end = bytes.len().min(max_bytes);
+            Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        }
+        ArtifactInput::File(path) => read_preview(path, max_bytes).ok(),
+    }
+}
+
+/// 判断检测输入是否以 gzip 魔数开头（代理把 gzip 编码的响应体原样保存成了 JAR）
+fn starts_with_gzip_magic(input: ArtifactInput) -> bool {
+    match input {
+        ArtifactInput::Bytes(bytes) => bytes.starts_with(GZIP_MAGIC),
+        ArtifactInput::File(path) => {
+            let mut head = [0u8; 2];
+            fs::File::open(path)
+                .and_then(|mut file| file.read_exact(&mut head))
+                .map(|_| head == GZIP_MAGIC)
+                .unwrap_or(false)
+        }
+    }
+}
+
+/// 由仓库布局 `<artifactId>/<version>/<artifactId>-<version>[-<classifier>].jar`
+/// 解析 classifier，主 JAR 返回空字符串；文件名不符合布局（如时间戳快照）时返回 `None`
+///
+/// 也识别 Gradle 缓存布局 `<artifactId>/<version>/<sha1>/<文件>`，跳过 sha1 目录后按同样规则解析
+fn jar_classifier<'a>(path: &Path, file_name: &'a str) -> Option<&'a str> {
+    let parent = path.parent()?;
+    classifier_in_version_dir(parent, file_name).or_else(|| {
+        let hash = parent.file_name()?.to_str()?;
+        if !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        classifier_in_version_dir(parent.parent()?, file_name)
     })
 }
 
-// ===================== 应用入口 =====================
+/// 按 `<artifactId>/<version>/` 目录名解析文件名中的 classifier，见 `jar_classifier`
+fn classifier_in_version_dir<'a>(version_dir: &Path, file_name: &'a str) -> Option<&'a str> {
+    let version = version_dir.file_name()?.to_str()?;
+    let artifact_id = version_dir.parent()?.file_name()?.to_str()?;
+    let rest = file_name
+        .rsplit_once('.')?
+        .0
+        .strip_prefix(artifact_id)?
+        .strip_prefix('-')?
+        .strip_prefix(version)?;
+    match rest.strip_prefix('-') {
+        Some(classifier) => Some(classifier),
+        None if rest.is_empty() => Some(""),
+        None => None,
+    }
+}
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_fs::init())
-        .invoke_handler(tauri::generate_handler![
-            get_maven_repo_path,
-            scan_invalid_artifacts,
-            clean_artifacts
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+/// JAR 大小低于其 classifier 的下限时返回原因，快速模式与完整检查共用
+///
+/// 只查看大小，不读取内容；仅对低于下限的构件计算 SHA-1 与 `size_exempt_sha1` 比对
+fn check_jar_size(path: &Path, input: ArtifactInput, config: &ScanConfig) -> Option<Finding> {
+    let file_name = path.file_name()?.to_str()?;
+    if !is_archive_file(file_name) {
+        return None;
+    }
+    record_check("jar_size");
+    let threshold = jar_classifier(path, file_name)
+        .and_then(|classifier| config.jar_size_thresholds.get(classifier))
+        .copied()
+        .unwrap_or(config.quick_min_jar_bytes);
+    let size = match input {
+        ArtifactInput::Bytes(bytes) => bytes.len() as u64,
+        ArtifactInput::File(path) => fs::metadata(path).ok()?.len(),
+    };
+    if size >= threshold || is_size_exempt(input, config) {
+        return None;
+    }
+    Some(Finding::with_detail(Reason::TooSmall, format!("{} 字节", size)))
+}
+
+/// 判断构件的 SHA-1 是否在 `size_exempt_sha1` 中（不区分大小写）
+fn is_size_exempt(input: ArtifactInput, config: &ScanConfig) -> bool {
+    if config.size_exempt_sha1.is_empty() {
+        return false;
+    }
+    let digest = match input {
+        ArtifactInput::Bytes(bytes) => digest_reader::<sha1::Sha1>(bytes),
+        ArtifactInput::File(path) => compute_sha1(path),
+    };
+    digest.is_ok_and(|digest| {
+        config
+            .size_exempt_sha1
+            .iter()
+            .any(|exempt| exempt.trim().eq_ignore_ascii_case(&digest))
+    })
+}
+
+/// 按目录和基础名排序后截断到 `max_results` 条，返回是否发生截断
+fn cap_results(artifacts: &mut Vec<InvalidArtifact>, max_results: Option<usize>) -> bool {
+    artifacts.sort_by(|a, b| (&a.folder, &a.base_name).cmp(&(&b.folder, &b.base_name)));
+    match max_results {
+        Some(max) if artifacts.len() > max => {
+            artifacts.truncate(max);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// 统计各检测原因对应的构件数量
+fn count_reasons(artifacts: &[InvalidArtifact]) -> HashMap<Reason, usize> {
+    let mut reason_counts: HashMap<Reason, usize> = HashMap::new();
+    for artifact in artifacts {
+        *reason_counts.entry(artifact.reason).or_insert(0) += 1;
+    }
+    reason_counts
+}
+
+/// 读取文件开头最多 `max_bytes` 字节，按 UTF-8 宽松解码
+fn read_preview(path: &Path, max_bytes: usize) -> std::io::Result<String> {
+    let file = fs::File::open(path)?;
+    let mut buf = Vec::with_capacity(max_bytes.min(64 * 1024));
+    file.take(max_bytes as u64).read_to_end(&mut buf)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// 转义 XML 文本内容中的特殊字符
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// 在 settings.xml 内容中更新或插入 `<localRepository>`，只改动相关片段，
+/// 其余内容（注释、缩进、属性顺序）原样保留
+fn set_local_repository(content: &str, repo_path: &str) -> Result<String, String> {
+    let doc = roxmltree::Document::parse(content).map_err(|e| format!("XML 解析失败: {}", e))?;
+    let root = doc.root_element();
+    if !root.has_tag_name("settings") {
+        return Err("根元素不是 <settings>".to_string());
+    }
+
+    let escaped = xml_escape(repo_path);
+    let mut updated = content.to_string();
+
+    // 已存在 localRepository：只替换其文本内容
+    if let Some(node) = root
+        .children()
+        .find(|n| n.is_element() && n.has_tag_name("localRepository"))
+    {
+        match node.children().find(|n| n.is_text()) {
+            Some(text) => updated.replace_range(text.range(), &escaped),
+            None => updated.replace_range(
+                node.range(),
+                &format!("<localRepository>{}</localRepository>", escaped),
+            ),
+        }
+        return Ok(updated);
+    }
+
+    // 不存在：插入到第一个子元素之前，沿用该元素的缩进
+    match root.children().find(|n| n.is_element()) {
+        Some(first) => {
+            let start = first.range().start;
+            let line_start = content[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let indent = &content[line_start..start];
+            let indent = if indent.trim().is_empty() {
+                indent
+            } else {
+                "  "
+            };
+            updated.insert_str(
+                start,
+                &format!("<localRepository>{}</localRepository>\n{}", escaped, indent),
+            );
+        }
+        None => {
+            // <settings/> 或空的 <settings></settings>：在结束标签前插入
+            let range = root.range();
+            let element = &content[range.clone()];
+            let replacement = match element.strip_suffix("/>") {
+                Some(open_tag) => format!(
+                    "{}>\n  <localRepository>{}</localRepository>\n</{}>",
+                    open_tag.trim_end(),
+                    escaped,
+                    root.tag_name().name()
+                ),
+                None => {
+                    let close = element.rfind("</").unwrap_or(element.len());
+                    format!(
+                        "{}\n  <localRepository>{}</localRepository>\n{}",
+                        element[..close].trim_end(),
+                        escaped,
+                        &element[close..]
+                    )
+                }
+            };
+            updated.replace_range(range, &replacement);
+        }
+    }
+
+    Ok(updated)
+}
+
+/// 计算指定年份 1 月 1 日 00:00 (UTC) 对应的时间点
+fn year_start(year: i32) -> SystemTime {
+    let is_leap = |y: i32| (y % 4 == 0 && y % 100 != 0) || y % 400 == 0;
+    let days: i64 = if year >= 1970 {
+        (1970..year)
+            .map(|y| if is_leap(y) { 366 } else { 365 })
+            .sum()
+    } else {
+        -(year..1970)
+            .map(|y| if is_leap(y) { 366 } else { 365 })
+            .sum::<i64>()
+    };
+    let secs = days * 24 * 60 * 60;
+    if secs >= 0 {
+        UNIX_EPOCH + Duration::from_secs(secs as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs())
+    }
+}
+
+/// 判断文件修改时间是否异常：早于下限年份或晚于当前时间 + 容忍值
+fn has_abnormal_mtime(path: &Path, floor_year: i32) -> bool {
+    let modified = match fs::metadata(path).and_then(|m| m.modified()) {
+        Ok(t) => t,
+        Err(_) => return false,
+    };
+
+    let floor = year_start(floor_year);
+    let ceiling = SystemTime::now() + MTIME_FUTURE_TOLERANCE;
+    modified < floor || modified > ceiling
+}
+
+/// Windows 下隐藏子进程的命令行窗口
+fn hide_console_window(command: &mut Command) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = command;
+}
+
+/// 简单的计数信号量，用于限制同时进行的操作数
+struct Semaphore {
+    permits: Mutex<usize>,
+    available: Condvar,
+}
+
+/// 信号量许可，离开作用域时自动归还
+struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Semaphore {
+    fn new(permits: usize) -> Self {
+        Self {
+            permits: Mutex::new(permits.max(1)),
+            available: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) -> SemaphorePermit<'_> {
+        let mut permits = self.permits.lock().unwrap_or_else(|e| e.into_inner());
+        while *permits == 0 {
+            permits = self
+                .available
+                .wait(permits)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *permits -= 1;
+        SemaphorePermit { semaphore: self }
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        let mut permits = self
+            .semaphore
+            .permits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *permits += 1;
+        self.semaphore.available.notify_one();
+    }
+}
+
+/// 扫描的暂停开关，工作线程在每个文件之间检查，暂停时阻塞直到恢复
+#[derive(Default)]
+pub struct ScanPause {
+    paused: AtomicBool,
+    lock: Mutex<()>,
+    resumed: Condvar,
+}
+
+impl ScanPause {
+    fn set_paused(&self, paused: bool) {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.paused.store(paused, Ordering::SeqCst);
+        if !paused {
+            self.resumed.notify_all();
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// 未暂停时立即返回，不加锁
+    fn wait_while_paused(&self) {
+        if !self.is_paused() {
+            return;
+        }
+        let mut guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        while self.is_paused() {
+            guard = self
+                .resumed
+                .wait(guard)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+/// 执行用户配置的外部校验命令，返回非零退出码时的原因
+///
+/// 命令按空白拆分参数，每个参数中的 `{path}` 替换为构件路径
+fn run_validator(validator_cmd: &str, path: &Path, timeout: Duration) -> Option<Finding> {
+    let path_str = display_path(path);
+    let mut parts = validator_cmd
+        .split_whitespace()
+        .map(|part| part.replace("{path}", &path_str));
+    let program = parts.next()?;
+
+    let mut command = Command::new(&program);
+    command.args(parts);
+    hide_console_window(&mut command);
+
+    match run_with_timeout(&mut command, timeout) {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let detail = stdout.trim();
+            if detail.is_empty() {
+                Some(Finding::with_detail(
+                    Reason::ValidatorFailed,
+                    format!("退出码 {:?}", output.status.code()),
+                ))
+            } else {
+                Some(Finding::with_detail(Reason::ValidatorFailed, detail))
+            }
+        }
+        Err(e) => Some(Finding::with_detail(
+            Reason::ValidatorFailed,
+            format!("命令执行失败: {}", e),
+        )),
+    }
+}
+
+/// 启动子进程并在限定时间内等待其结束，超时则杀掉进程并返回错误
+fn run_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output, String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    // 在独立线程中读取输出，避免管道缓冲区写满导致子进程阻塞
+    let stdout_reader = child.stdout.take().map(|mut out| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            out.read_to_end(&mut buf).ok();
+            buf
+        })
+    });
+    let stderr_reader = child.stderr.take().map(|mut err| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            err.read_to_end(&mut buf).ok();
+            buf
+        })
+    });
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {
+                if started.elapsed() >= timeout {
+                    child.kill().ok();
+                    child.wait().ok();
+                    return Err(format!("执行超时 ({} 秒)", timeout.as_secs()));
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => {
+                child.kill().ok();
+                return Err(e.to_string());
+            }
+        }
+    };
+
+    let stdout = stdout_reader
+        .and_then(|h| h.join().ok())
+        .unwrap_or_default();
+    let stderr = stderr_reader
+        .and_then(|h| h.join().ok())
+        .unwrap_or_default();
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+// ===================== Tauri Commands =====================
+
+#[tauri::command]
+fn set_repo_path_override(state: State<'_, AppState>, path: String) -> Result<(), String> {
+    ensure_repo_dir(Path::new(&path))?;
+
+    let mut guard = state
+        .repo_path_override
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))?;
+    *guard = Some(path);
+    Ok(())
+}
+
+#[tauri::command]
+fn clear_repo_path_override(state: State<'_, AppState>) -> Result<(), String> {
+    let mut guard = state
+        .repo_path_override
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))?;
+    *guard = None;
+    Ok(())
+}
+
+/// 检测 Maven 仓库路径，返回结果中附带逐步检测的日志（打包后的应用看不到终端输出）
+#[tauri::command]
+fn get_maven_repo_path(
+    state: State<'_, AppState>,
+    settings_path: Option<String>,
+) -> Result<RepoLocation, String> {
+    let env = DetectEnv::from_process();
+    let (result, log) = with_log_capture(|| detect_repo_location(&state, settings_path, &env));
+    result.map(|location| RepoLocation { log, ..location })
+}
+
+/// 从 settings.xml 解析 localRepository，文件不存在、无法解析或指向文件而非目录时返回 `None`
+fn parse_local_repo(settings_path: &Path) -> Option<String> {
+    log_msg!(
+        "[DEBUG] 尝试读取配置文件: {}",
+        "[DEBUG] Reading settings file: {}",
+        settings_path.display()
+    );
+
+    if !settings_path.exists() {
+        log_msg!("[DEBUG] 文件不存在", "[DEBUG] File does not exist");
+        return None;
+    }
+
+    let content = match fs::read_to_string(settings_path) {
+        Ok(c) => {
+            log_msg!(
+                "[DEBUG] 文件读取成功，长度: {} 字节",
+                "[DEBUG] File read, length: {} bytes",
+                c.len()
+            );
+            c
+        }
+        Err(e) => {
+            log_msg!(
+                "[DEBUG] 文件读取失败: {}",
+                "[DEBUG] Failed to read file: {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    let doc = match roxmltree::Document::parse(&content) {
+        Ok(d) => {
+            log_msg!("[DEBUG] XML 解析成功", "[DEBUG] XML parsed");
+            d
+        }
+        Err(e) => {
+            log_msg!(
+                "[DEBUG] XML 解析失败: {}",
+                "[DEBUG] Failed to parse XML: {}",
+                e
+            );
+            return None;
+        }
+    };
+
+    for node in doc.descendants() {
+        if node.has_tag_name("localRepository") {
+            if let Some(repo_path) = node.text() {
+                let trimmed = repo_path.trim();
+                // 不存在或不是目录（如误写为 /etc/hosts）的配置视为无效
+                if !trimmed.is_empty() && !Path::new(trimmed).is_dir() {
+                    log_msg!(
+                        "[DEBUG] localRepository 不是已存在的目录，忽略: {}",
+                        "[DEBUG] localRepository is not an existing directory, ignoring: {}",
+                        trimmed
+                    );
+                    return None;
+                }
+                if !trimmed.is_empty() {
+                    log_msg!(
+                        "[DEBUG] ✅ 找到 localRepository: {}",
+                        "[DEBUG] ✅ Found localRepository: {}",
+                        trimmed
+                    );
+                    return Some(trimmed.to_string());
+                }
+            }
+        }
+    }
+
+    log_msg!(
+        "[DEBUG] 未找到 <localRepository> 标签",
+        "[DEBUG] No <localRepository> element found"
+    );
+    None
+}
+
+/// 仓库检测读取的进程环境；测试时传入独立的值，无需修改全局环境变量
+struct DetectEnv {
+    maven_home: Option<String>,
+    m2_home: Option<String>,
+    /// 查找 `mvn` 命令及推断 Maven 路径使用的 PATH，`None` 时沿用进程的 PATH
+    path: Option<std::ffi::OsString>,
+    home_dir: Option<PathBuf>,
+}
+
+impl DetectEnv {
+    fn from_process() -> Self {
+        Self {
+            maven_home: std::env::var("MAVEN_HOME").ok(),
+            m2_home: std::env::var("M2_HOME").ok(),
+            path: std::env::var_os("PATH"),
+            home_dir: dirs::home_dir(),
+        }
+    }
+}
+
+/// `get_maven_repo_path` 的检测逻辑，按优先级依次尝试各个来源
+fn detect_repo_location(
+    state: &AppState,
+    settings_path: Option<String>,
+    env: &DetectEnv,
+) -> Result<RepoLocation, String> {
+    // 辅助函数：通过 mvn -v 命令获取 Maven 安装路径
+    fn get_maven_home_from_command(path: Option<&std::ffi::OsStr>) -> Option<String> {
+        log_msg!("[DEBUG] 尝试执行 mvn 命令", "[DEBUG] Trying to run mvn");
+
+        // Windows 下尝试 mvn.cmd 和 mvn.bat
+        let maven_commands = if cfg!(target_os = "windows") {
+            vec!["mvn.cmd", "mvn.bat", "mvn"]
+        } else {
+            vec!["mvn"]
+        };
+
+        for cmd in maven_commands {
+            log_msg!("[DEBUG] 尝试命令: {}", "[DEBUG] Trying command: {}", cmd);
+
+            // 创建命令构建器
+            let mut command = Command::new(cmd);
+            command.arg("-v");
+            if let Some(path) = path {
+                command.env("PATH", path);
+            }
+            hide_console_window(&mut command);
+
+            let output = match run_with_timeout(&mut command, MVN_COMMAND_TIMEOUT) {
+                Ok(o) => {
+                    log_msg!("[DEBUG] {} 执行成功", "[DEBUG] {} succeeded", cmd);
+                    o
+                }
+                Err(e) => {
+                    log_msg!("[DEBUG] {} 执行失败: {}", "[DEBUG] {} failed: {}", cmd, e);
+                    continue;
+                }
+            };
+
+            if !output.status.success() {
+                log_msg!(
+                    "[DEBUG] {} 返回非零状态码",
+                    "[DEBUG] {} exited with a non-zero status",
+                    cmd
+                );
+                continue;
+            }
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            log_msg!(
+                "[DEBUG] mvn -v 输出:\n{}",
+                "[DEBUG] mvn -v output:\n{}",
+                stdout
+            );
+
+            for line in stdout.lines() {
+                if line.starts_with("Maven home:") {
+                    let maven_home = line.strip_prefix("Maven home:")?.trim();
+                    log_msg!(
+                        "[DEBUG] ✅ 从 mvn -v 解析到 Maven home: {}",
+                        "[DEBUG] ✅ Maven home from mvn -v: {}",
+                        maven_home
+                    );
+                    return Some(maven_home.to_string());
+                }
+            }
+        }
+
+        log_msg!(
+            "[DEBUG] 所有 mvn 命令尝试均失败",
+            "[DEBUG] All mvn command attempts failed"
+        );
+        None
+    }
+
+    // 0. 用户手动指定的路径：存在即直接返回，跳过所有检测
+    let override_path = state
+        .repo_path_override
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))?
+        .clone();
+    if let Some(path) = override_path {
+        if Path::new(&path).is_dir() {
+            log_msg!(
+                "[覆盖路径] 使用用户指定的仓库路径: {}",
+                "[Override] Using user-specified repository path: {}",
+                path
+            );
+            return Ok(RepoLocation::new(path, RepoSource::Override, false));
+        }
+        log_msg!(
+            "[覆盖路径] 指定的路径已不存在或不是目录，回退到自动检测: {}",
+            "[Override] Specified path is missing or not a directory, falling back to detection: {}",
+            path
+        );
+    }
+
+    log_msg!(
+        "\n========== 开始检测 Maven 仓库路径 ==========",
+        "\n========== Detecting Maven repository path =========="
+    );
+
+    // 0.5 用户指定的 settings.xml（相当于 `mvn -s`）：优先于所有自动检测
+    if let Some(settings_path) = settings_path.filter(|p| !p.trim().is_empty()) {
+        log_msg!(
+            "[步骤 0.5] 尝试读取用户指定的配置文件",
+            "[Step 0.5] Reading user-specified settings file"
+        );
+        if let Some(repo) = parse_local_repo(Path::new(settings_path.trim())) {
+            log_msg!(
+                "========== ✅ 检测成功，返回路径: {} ==========\n",
+                "========== ✅ Detected repository path: {} ==========\n",
+                repo
+            );
+            return Ok(RepoLocation::new(repo, RepoSource::CustomSettings, false));
+        }
+    }
+
+    // 1. 最高优先级：通过 mvn -v 命令获取的 Maven 全局配置
+    log_msg!(
+        "[步骤 1] 尝试通过 mvn -v 命令检测",
+        "[Step 1] Detecting via mvn -v"
+    );
+    let maven_home = get_maven_home_from_command(env.path.as_deref());
+    let maven_found = maven_home.is_some();
+    if let Some(maven_home) = maven_home {
+        let global_settings = Path::new(&maven_home).join("conf").join("settings.xml");
+        if let Some(repo) = parse_local_repo(&global_settings) {
+            log_msg!(
+                "========== ✅ 检测成功，返回路径: {} ==========\n",
+                "========== ✅ Detected repository path: {} ==========\n",
+                repo
+            );
+            return Ok(RepoLocation::new(repo, RepoSource::MvnCommand, true));
+        }
+    }
+
+    // 2. 次优先级：环境变量指定的 Maven 全局配置
+    log_msg!(
+        "[步骤 2] 尝试读取环境变量 MAVEN_HOME / M2_HOME",
+        "[Step 2] Reading MAVEN_HOME / M2_HOME"
+    );
+    let mut maven_home_candidates = vec![env.maven_home.clone(), env.m2_home.clone()];
+
+    for (i, maven_home) in maven_home_candidates.iter().enumerate() {
+        if let Some(home) = maven_home {
+            log_msg!(
+                "[DEBUG] 环境变量 {} = {}",
+                "[DEBUG] Environment variable {} = {}",
+                if i == 0 { "MAVEN_HOME" } else { "M2_HOME" },
+                home
+            );
+        } else {
+            log_msg!(
+                "[DEBUG] 环境变量 {} 未设置",
+                "[DEBUG] Environment variable {} is not set",
+                if i == 0 { "MAVEN_HOME" } else { "M2_HOME" }
+            );
+        }
+    }
+
+    // 3. 尝试从 PATH 环境变量推断 Maven 路径
+    log_msg!(
+        "[步骤 2.5] 尝试从 PATH 环境变量推断 Maven 路径",
+        "[Step 2.5] Inferring Maven home from PATH"
+    );
+    if let Some(path_env) = env.path.as_ref().and_then(|p| p.to_str()) {
+        log_msg!("[DEBUG] PATH 环境变量已设置", "[DEBUG] PATH is set");
+        for path in path_env.split(';') {
+            if path.to_lowercase().contains("maven") && path.to_lowercase().contains("bin") {
+                log_msg!(
+                    "[DEBUG] 发现可能的 Maven bin 目录: {}",
+                    "[DEBUG] Possible Maven bin directory: {}",
+                    path
+                );
+                // Maven bin 路径的父目录就是 Maven home
+                if let Some(parent) = Path::new(path).parent() {
+                    let maven_home = parent.to_string_lossy().to_string();
+                    log_msg!(
+                        "[DEBUG] 推断的 Maven home: {}",
+                        "[DEBUG] Inferred Maven home: {}",
+                        maven_home
+                    );
+                    maven_home_candidates.push(Some(maven_home));
+                }
+            }
+        }
+    }
+
+    for maven_home in maven_home_candidates.into_iter().flatten() {
+        let global_settings = Path::new(&maven_home).join("conf").join("settings.xml");
+        if let Some(repo) = parse_local_repo(&global_settings) {
+            log_msg!(
+                "========== ✅ 检测成功，返回路径: {} ==========\n",
+                "========== ✅ Detected repository path: {} ==========\n",
+                repo
+            );
+            return Ok(RepoLocation::new(repo, RepoSource::EnvVar, true));
+        }
+    }
+
+    // 3. 第三优先级：用户级别 settings.xml (~/.m2/settings.xml)
+    log_msg!(
+        "[步骤 3] 尝试读取用户级配置 ~/.m2/settings.xml",
+        "[Step 3] Reading user settings ~/.m2/settings.xml"
+    );
+    if let Some(home_dir) = &env.home_dir {
+        log_msg!(
+            "[DEBUG] 用户主目录: {}",
+            "[DEBUG] Home directory: {}",
+            home_dir.display()
+        );
+        let user_settings = home_dir.join(".m2").join("settings.xml");
+        if let Some(repo) = parse_local_repo(&user_settings) {
+            log_msg!(
+                "========== ✅ 检测成功，返回路径: {} ==========\n",
+                "========== ✅ Detected repository path: {} ==========\n",
+                repo
+            );
+            return Ok(RepoLocation::new(repo, RepoSource::UserSettings, maven_found));
+        }
+    }
+
+    // 4. 兜底：返回默认路径 ~/.m2/repository
+    log_msg!("[步骤 4] 使用默认路径", "[Step 4] Using default path");
+    let home_dir = env.home_dir.as_ref().ok_or("无法获取用户主目录")?;
+    let default_repo = home_dir.join(".m2").join("repository");
+    let default_path = default_repo.to_string_lossy().to_string();
+    log_msg!(
+        "========== ⚠️ 使用默认路径: {} ==========\n",
+        "========== ⚠️ Using default path: {} ==========\n",
+        default_path
+    );
+    Ok(RepoLocation::new(default_path, RepoSource::Default, maven_found))
+}
+
+#[tauri::command]
+fn write_local_repository(settings_path: String, repo_path: String) -> Result<(), String> {
+    let settings_file = Path::new(&settings_path);
+
+    let content = if settings_file.exists() {
+        let original = fs::read_to_string(settings_file)
+            .map_err(|e| format!("无法读取配置文件 {}: {}", settings_file.display(), e))?;
+        set_local_repository(&original, &repo_path)?
+    } else {
+        if let Some(parent) = settings_file.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("无法创建目录 {}: {}", parent.display(), e))?;
+        }
+        SETTINGS_SKELETON.replace("{}", &xml_escape(&repo_path))
+    };
+
+    fs::write(settings_file, content)
+        .map_err(|e| format!("无法写入配置文件 {}: {}", settings_file.display(), e))?;
+
+    log_msg!(
+        "[写入配置] 已将 localRepository 设置为 {}: {}",
+        "[Write settings] Set localRepository to {}: {}",
+        repo_path,
+        settings_file.display()
+    );
+
+    Ok(())
+}
+
+/// 开始新的扫描：旧结果不再有效，上一次扫描遗留的暂停状态也不应阻塞新的扫描
+///
+/// 返回供扫描线程使用的暂停开关；扫描期间不持有任何状态锁，其他命令照常执行
+fn begin_scan(state: &AppState) -> Result<Arc<ScanPause>, String> {
+    store_last_scan(state, None)?;
+    let pause = Arc::clone(&state.scan_pause);
+    pause.set_paused(false);
+    Ok(pause)
+}
+
+/// 更新缓存的最近一次扫描结果
+fn store_last_scan(state: &AppState, report: Option<ScanReport>) -> Result<(), String> {
+    *state
+        .last_scan
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))? = report;
+    Ok(())
+}
+
+/// 扫描成功后缓存结果，并通过 `emit` 发送一次 `scan-complete` 事件
+///
+/// 无论前端是否使用命令的返回值，都在同一处收到完成通知；发送失败只记录日志
+fn complete_scan(
+    state: &AppState,
+    report: &ScanReport,
+    emit: impl FnOnce(&str, ScanSummary) -> Result<(), String>,
+) -> Result<(), String> {
+    store_last_scan(state, Some(report.clone()))?;
+    if let Err(e) = emit(SCAN_COMPLETE_EVENT, ScanSummary::from(report)) {
+        log_msg!(
+            "[多线程扫描] 发送完成事件失败: {}",
+            "[Parallel scan] Failed to emit completion event: {}",
+            e
+        );
+    }
+    Ok(())
+}
+
+/// 扫描耗时较长，放到阻塞线程中执行，避免占用命令线程
+async fn run_scan_blocking(
+    repo_path: String,
+    config: ScanConfig,
+    pause: Arc<ScanPause>,
+) -> Result<ScanReport, String> {
+    tauri::async_runtime::spawn_blocking(move || run_scan(Path::new(&repo_path), config, &pause))
+        .await
+        .map_err(|e| format!("扫描任务失败: {}", e))?
+}
+
+#[tauri::command]
+async fn scan_invalid_artifacts(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    repo_path: String,
+    config: Option<ScanConfig>,
+) -> Result<ScanReport, String> {
+    let pause = begin_scan(&state)?;
+    let report = run_scan_blocking(repo_path, config.unwrap_or_default(), pause).await?;
+
+    complete_scan(&state, &report, |event, summary| {
+        app.emit(event, summary).map_err(|e| e.to_string())
+    })?;
+
+    Ok(report)
+}
+
+/// 扫描后立即清理发现的全部损坏构件，供脚本一次调用完成
+///
+/// 会直接删除文件，必须显式传入 `confirm: true`
+#[tauri::command]
+async fn scan_and_clean(
+    state: State<'_, AppState>,
+    repo_path: String,
+    config: Option<ScanConfig>,
+    options: Option<CleanOptions>,
+    confirm: bool,
+) -> Result<ScanAndCleanResult, String> {
+    if !confirm {
+        return Err("scan_and_clean 会直接删除文件，需要传入 confirm: true".to_string());
+    }
+
+    let pause = begin_scan(&state)?;
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        // 需要清理发现的全部构件，不能截断结果
+        let config = ScanConfig {
+            max_results: None,
+            ..config.unwrap_or_default()
+        };
+        let report = run_scan(Path::new(&repo_path), config, &pause)?;
+        let items = report
+            .artifacts
+            .iter()
+            .map(|a| CleanItem {
+                folder: a.folder.clone(),
+                base_name: a.base_name.clone(),
+            })
+            .collect();
+        let clean = clean_artifacts(items, repo_path, options)?;
+
+        log_msg!(
+            "[扫描并清理] 发现 {} 个损坏的构件，删除了 {} 个构件文件、{} 个元数据文件",
+            "[Scan and clean] Found {} invalid artifacts, deleted {} artifact files and {} metadata files",
+            report.artifacts.len(),
+            clean.artifacts_deleted,
+            clean.metadata_deleted
+        );
+
+        Ok(ScanAndCleanResult { report, clean })
+    })
+    .await
+    .map_err(|e| format!("扫描任务失败: {}", e))??;
+
+    store_last_scan(&state, Some(result.report.clone()))?;
+    Ok(result)
+}
+
+/// 返回最近一次完成的扫描结果，从未扫描或扫描进行中时返回 `None`
+#[tauri::command]
+fn get_last_scan_result(state: State<'_, AppState>) -> Result<Option<ScanReport>, String> {
+    load_last_scan(&state)
+}
+
+/// `get_last_scan_result` 的实现
+fn load_last_scan(state: &AppState) -> Result<Option<ScanReport>, String> {
+    let guard = state
+        .last_scan
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))?;
+    Ok(guard.clone())
+}
+
+/// 参与对比的扫描报告：前端传入的报告对象，或导出的 JSON 文件路径
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ReportSource {
+    Path(String),
+    Report(ScanReport),
+}
+
+impl ReportSource {
+    fn load(self) -> Result<ScanReport, String> {
+        match self {
+            ReportSource::Report(report) => Ok(report),
+            ReportSource::Path(path) => {
+                let content = fs::read_to_string(&path)
+                    .map_err(|e| format!("无法读取扫描报告 {}: {}", path, e))?;
+                serde_json::from_str(&content)
+                    .map_err(|e| format!("无法解析扫描报告 {}: {}", path, e))
+            }
+        }
+    }
+}
+
+/// 两次扫描结果的差异
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDiff {
+    /// 仅出现在新报告中的条目（新增损坏）
+    added: Vec<InvalidArtifact>,
+    /// 仅出现在旧报告中的条目（已修复）
+    removed: Vec<InvalidArtifact>,
+}
+
+/// 按 目录 + 构件名 + 原因 对比两份扫描报告，用于构建前后的回归追踪
+fn diff_reports(before: ScanReport, after: ScanReport) -> ReportDiff {
+    fn key(artifact: &InvalidArtifact) -> (&str, &str, Reason) {
+        (&artifact.folder, &artifact.base_name, artifact.reason)
+    }
+
+    let before_keys: HashSet<_> = before.artifacts.iter().map(key).collect();
+    let after_keys: HashSet<_> = after.artifacts.iter().map(key).collect();
+    let added = after
+        .artifacts
+        .iter()
+        .filter(|a| !before_keys.contains(&key(a)))
+        .cloned()
+        .collect();
+    let removed = before
+        .artifacts
+        .iter()
+        .filter(|a| !after_keys.contains(&key(a)))
+        .cloned()
+        .collect();
+
+    ReportDiff { added, removed }
+}
+
+/// 对比两份扫描报告，返回新增与已修复的损坏构件
+///
+/// 每份报告既可以是扫描返回的 `ScanReport` 对象，也可以是导出的 JSON 文件路径
+#[tauri::command]
+fn diff_scan_reports(before: ReportSource, after: ReportSource) -> Result<ReportDiff, String> {
+    Ok(diff_reports(before.load()?, after.load()?))
+}
+
+/// 扫描时共享的并发限制
+struct ScanLimits {
+    /// 同时打开的文件数
+    open_files: Semaphore,
+    /// 同时运行的外部校验命令数
+    validator: Semaphore,
+    validator_timeout: Duration,
+}
+
+impl ScanLimits {
+    fn new(config: &ScanConfig) -> Self {
+        Self {
+            open_files: Semaphore::new(config.max_open_files),
+            validator: Semaphore::new(config.validator_concurrency),
+            validator_timeout: Duration::from_secs(config.validator_timeout_secs),
+        }
+    }
+}
+
+/// 按配置检查单个文件，损坏时返回对应的条目；仓库扫描与单文件校验共用
+///
+/// 校验和文件在此只检查是否为空，冲突需要按构件分组比较，由 `find_checksum_conflicts`
+/// 单独处理；`repo_root` 未知时跳过需要定位其他构件的检查（如父 POM）
+fn check_file(
+    path: &Path,
+    repo_root: Option<&Path>,
+    config: &ScanConfig,
+    limits: &ScanLimits,
+) -> Option<InvalidArtifact> {
+    let file_name = path.file_name()?.to_str()?;
+
+    // 校验和文件：零字节时报告（或重新生成），冲突检查单独处理
+    if checksum_sidecar(file_name).is_some() {
+        if !config.check_empty_checksums {
+            return None;
+        }
+        record_check("empty_checksum");
+        return Some(InvalidArtifact::new(
+            display_path(path.parent()?),
+            file_name.to_string(),
+            check_empty_checksum(path, file_name, config)?,
+        ));
+    }
+
+    // 残留锁文件：近期仍有修改的可能正被占用，跳过
+    if is_lock_file(file_name) {
+        record_check("stale_lock");
+        if !is_older_than(path, config.lock_min_age_secs) {
+            return None;
+        }
+        return Some(InvalidArtifact::new(
+            display_path(path.parent()?),
+            file_name.to_string(),
+            Finding::new(Reason::StaleLock),
+        ));
+    }
+
+    // 未完成的下载临时文件
+    if is_temp_download_file(file_name) {
+        record_check("partial_download");
+        return Some(InvalidArtifact::new(
+            display_path(path.parent()?),
+            file_name.to_string(),
+            Finding::new(Reason::PartialDownload),
+        ));
+    }
+
+    // 放错版本目录的构件：只看文件名，快速模式下同样检查
+    if config.check_version_mismatch && !is_maven_metadata_file(file_name) {
+        record_check("version_dir");
+        if let Some(finding) = check_version_dir(path, file_name) {
+            return Some(InvalidArtifact::new(
+                display_path(path.parent()?),
+                artifact_base_name(file_name),
+                finding,
+            ));
+        }
+    }
+
+    // 以下检查需要打开文件（快速模式下也可能为 `size_exempt_sha1` 计算摘要），限制同时打开的文件数
+    let open_file_permit = limits.open_files.acquire();
+
+    if config.quick {
+        return Some(InvalidArtifact::new(
+            display_path(path.parent()?),
+            artifact_base_name(file_name),
+            classify_artifact(path, ArtifactInput::File(path), config)?,
+        ));
+    }
+
+    // 损坏的 maven-metadata*.xml：删除后由 Maven 重新生成
+    if is_maven_metadata_file(file_name) {
+        record_check("metadata_xml");
+        if is_valid_maven_metadata(path) {
+            return None;
+        }
+        return Some(InvalidArtifact::new(
+            display_path(path.parent()?),
+            file_name.to_string(),
+            Finding::new(Reason::CorruptMetadata),
+        ));
+    }
+
+    let finding = classify_artifact(path, ArtifactInput::File(path), config).or_else(|| {
+        // 父 POM 缺失：需要仓库根目录来定位父 POM
+        let repo_root = repo_root.filter(|_| config.check_parent_pom)?;
+        if !file_name.ends_with(".pom") {
+            return None;
+        }
+        record_check("parent_pom");
+        check_parent_pom(path, repo_root)
+    });
+    drop(open_file_permit);
+
+    let finding = finding.or_else(|| {
+        // 外部校验命令：限制并发，避免同时启动过多子进程
+        let validator_cmd = config.validator_cmd.as_deref()?;
+        let _permit = limits.validator.acquire();
+        record_check("validator");
+        run_validator(validator_cmd, path, limits.validator_timeout)
+    })?;
+    Some(InvalidArtifact::new(
+        display_path(path.parent()?),
+        artifact_base_name(file_name),
+        finding,
+    ))
+}
+
+thread_local! {
+    /// 当前线程记录的已执行检查项，`None` 表示未在记录
+    static CHECK_RECORD: RefCell<Option<Vec<&'static str>>> = const { RefCell::new(None) };
+}
+
+/// 记录一项实际执行的检查，当前线程未在记录时不做任何事
+fn record_check(name: &'static str) {
+    CHECK_RECORD.with(|record| {
+        if let Some(checks) = record.borrow_mut().as_mut() {
+            checks.push(name);
+        }
+    });
+}
+
+/// 执行 `f` 并返回期间当前线程实际执行的检查项，供调试日志使用
+fn with_check_record<T>(f: impl FnOnce() -> T) -> (T, Vec<&'static str>) {
+    let previous = CHECK_RECORD.with(|record| record.replace(Some(Vec::new())));
+    let result = f();
+    let checks = CHECK_RECORD
+        .with(|record| record.replace(previous))
+        .unwrap_or_default();
+    (result, checks)
+}
+
+/// 扫描决策调试日志，每行一个 JSON 对象；多个 Rayon 线程共享同一个带缓冲的写入器
+struct DebugLog {
+    writer: Mutex<BufWriter<fs::File>>,
+}
+
+impl DebugLog {
+    fn create(path: &str) -> Result<Self, String> {
+        let file =
+            fs::File::create(path).map_err(|e| format!("无法创建调试日志 {}: {}", path, e))?;
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// 记录一个文件的检查结果，写入失败时忽略，不影响扫描
+    fn record(&self, path: &Path, checks: &[&str], invalid: Option<&InvalidArtifact>) {
+        let line = serde_json::json!({
+            "path": display_path(path),
+            "checks": checks,
+            "verdict": invalid.map(|a| a.reason),
+            "message": invalid.map(|a| a.message.as_str()),
+        });
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(writer, "{}", line).ok();
+    }
+
+    fn flush(&self) {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.flush().ok();
+    }
+}
+
+/// 创建本次扫描使用的线程池，返回线程池及其线程数
+///
+/// 每次扫描使用独立的线程池，线程数按本次配置生效
+fn build_scan_pool(config: &ScanConfig) -> Result<(rayon::ThreadPool, usize), String> {
+    // 根据 CPU 核心数配置线程池 (IO 密集型,默认核心数 * 4)，可由配置覆盖
+    let cpu_count = num_cpus::get();
+    let thread_count = config
+        .thread_count
+        .unwrap_or(cpu_count * 4)
+        .clamp(1, MAX_SCAN_THREADS);
+
+    log_msg!(
+        "[多线程扫描] CPU 核心数: {}, 线程池大小: {}",
+        "[Parallel scan] CPU cores: {}, thread pool size: {}",
+        cpu_count,
+        thread_count
+    );
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(thread_count)
+        .build()
+        .map_err(|e| format!("创建线程池失败: {}", e))?;
+    Ok((pool, thread_count))
+}
+
+/// 扫描仓库中损坏的构件，`scan_invalid_artifacts` 的同步实现
+///
+/// 每检查一个文件前都会查看 `pause`，暂停期间工作线程阻塞等待
+fn run_scan(repo_path: &Path, config: ScanConfig, pause: &ScanPause) -> Result<ScanReport, String> {
+    ensure_repo_dir(repo_path)?;
+    let repo_path = &canonical_repo_path(repo_path);
+
+    // 安全检查：目标不像 Maven 仓库时拒绝扫描，需显式传入 force
+    if !config.force && !looks_like_repo_path(repo_path) {
+        return Err(format!(
+            "{}: 路径中没有 .m2 或 repository 目录，可能不是 Maven 仓库: {}；确认无误后传入 force: true 重新扫描",
+            SCAN_REFUSED_PREFIX,
+            repo_path.display()
+        ));
+    }
+
+    let (pool, thread_count) = build_scan_pool(&config)?;
+
+    let limits = ScanLimits::new(&config);
+    let repo_root = to_long_path(repo_path);
+    let debug_log = config
+        .debug_log_path
+        .as_deref()
+        .map(DebugLog::create)
+        .transpose()?;
+
+    let scan_started = Instant::now();
+
+    // 快速模式下跳过所有需要读取文件内容的检查
+    let content_checks = !config.quick;
+
+    // 第一阶段：收集所有待检查的文件路径，文件总数超过上限时拒绝扫描
+    let max_files = if config.force { usize::MAX } else { config.max_files };
+    let files_to_check = collect_repo_files_guarded(repo_path, max_files, |name| {
+        is_configured_artifact(name, &config)
+            || (config.detect_lock_files && is_lock_file(name))
+            || (config.detect_temp_files && is_temp_download_file(name))
+            || (content_checks && config.check_metadata && is_maven_metadata_file(name))
+            || ((config.check_empty_checksums
+                || (content_checks && config.check_checksum_conflicts))
+                && checksum_sidecar(name).is_some())
+    })?;
+
+    let collect_ms = scan_started.elapsed().as_millis() as u64;
+    let check_started = Instant::now();
+
+    log_msg!(
+        "[多线程扫描] 发现 {} 个 JAR/POM 文件,开始并行检查...",
+        "[Parallel scan] Found {} JAR/POM files, checking in parallel...",
+        files_to_check.len()
+    );
+
+    // 第二阶段：并行检查所有文件
+    let mut invalid_artifacts: Vec<InvalidArtifact> = pool.install(|| {
+        files_to_check
+            .par_iter() // 使用 Rayon 并行迭代器
+            .filter_map(|path| {
+                pause.wait_while_paused();
+                let (invalid, checks) =
+                    with_check_record(|| check_file(path, Some(&repo_root), &config, &limits));
+                if let Some(debug_log) = &debug_log {
+                    // 校验和冲突按构件分组检查，结果在下面单独记录
+                    if !checks.is_empty() {
+                        debug_log.record(path, &checks, invalid.as_ref());
+                    }
+                }
+                invalid
+            })
+            .collect()
+    });
+
+    // 同一构件的多个校验和文件互相冲突
+    if content_checks && config.check_checksum_conflicts {
+        let conflicts =
+            pool.install(|| find_checksum_conflicts(&files_to_check, &limits.open_files));
+        if let Some(debug_log) = &debug_log {
+            for conflict in &conflicts {
+                let path = Path::new(&conflict.folder).join(&conflict.base_name);
+                debug_log.record(&path, &["checksum_conflicts"], Some(conflict));
+            }
+        }
+        invalid_artifacts.extend(conflicts);
+    }
+
+    if let Some(debug_log) = &debug_log {
+        debug_log.flush();
+    }
+
+    let reason_counts = count_reasons(&invalid_artifacts);
+    let total_invalid = invalid_artifacts.len();
+    let truncated = cap_results(&mut invalid_artifacts, config.max_results);
+
+    let check_ms = check_started.elapsed().as_millis() as u64;
+    let duration_ms = scan_started.elapsed().as_millis() as u64;
+
+    log_msg!(
+        "[多线程扫描] 扫描完成,发现 {} 个损坏的构件,耗时 {} ms (收集 {} ms, 检查 {} ms)",
+        "[Parallel scan] Done, {} invalid artifacts found in {} ms (collect {} ms, check {} ms)",
+        total_invalid,
+        duration_ms,
+        collect_ms,
+        check_ms
+    );
+
+    Ok(ScanReport {
+        reason_counts,
+        artifacts: invalid_artifacts,
+        files_scanned: files_to_check.len(),
+        duration_ms,
+        collect_ms,
+        check_ms,
+        thread_count,
+        total_invalid,
+        truncated,
+    })
+}
+
+/// 按扫描配置重新检查单个文件，用于用户手动修复后确认结果，无需重新扫描整个仓库
+///
+/// 文件完好时返回 `None`；传入 `repo_root` 时才会执行需要定位其他构件的检查（如父 POM）
+#[tauri::command]
+fn check_single_artifact(
+    path: String,
+    repo_root: Option<String>,
+    config: Option<ScanConfig>,
+) -> Result<Option<InvalidArtifact>, String> {
+    let config = config.unwrap_or_default();
+    let repo_root = repo_root.map(|root| canonical_repo_path(Path::new(&root)));
+    let path = Path::new(&path);
+
+    if !path.is_file() {
+        return Err(format!("文件不存在或不是文件: {}", path.display()));
+    }
+    // 与扫描结果保持一致的目录格式
+    let path = fs::canonicalize(to_long_path(path)).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("无法解析文件名: {}", path.display()))?;
+
+    let limits = ScanLimits::new(&config);
+    if let Some(invalid) = check_file(&path, repo_root.as_deref(), &config, &limits) {
+        return Ok(Some(invalid));
+    }
+
+    // 同目录下属于该构件的校验和文件互相冲突
+    if !config.quick && config.check_checksum_conflicts {
+        let folder = path.parent().unwrap_or(path.as_path());
+        let sidecars: Vec<PathBuf> = fs::read_dir(folder)
+            .map_err(|e| format!("无法读取目录 {}: {}", folder.display(), e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(checksum_sidecar)
+                    .is_some_and(|(artifact, _)| artifact == file_name)
+            })
+            .collect();
+        return Ok(find_checksum_conflicts(&sidecars, &limits.open_files)
+            .into_iter()
+            .next());
+    }
+
+    Ok(None)
+}
+
+/// 查找超过 `days` 天未被访问的 JAR，按文件大小降序返回
+///
+/// 优先使用文件访问时间 (atime)，平台不支持时退回修改时间。注意以 `noatime`
+/// 挂载的文件系统不会更新访问时间，`relatime` 也只会延迟更新，此时结果会偏向
+/// "未使用"，仅供参考。
+#[tauri::command]
+fn scan_unused_artifacts(repo_path: String, days: u64) -> Result<Vec<UnusedArtifact>, String> {
+    let repo_path = Path::new(&repo_path);
+
+    ensure_repo_dir(repo_path)?;
+    let repo_path = &canonical_repo_path(repo_path);
+
+    let now = SystemTime::now();
+    let files = collect_artifact_files(repo_path, &[ArtifactType::Jar]);
+
+    let mut unused: Vec<UnusedArtifact> = files
+        .par_iter()
+        .filter_map(|path| {
+            let metadata = fs::metadata(path).ok()?;
+            let last_used = metadata.accessed().or_else(|_| metadata.modified()).ok()?;
+            let idle_days = now.duration_since(last_used).ok()?.as_secs() / (24 * 60 * 60);
+            if idle_days < days {
+                return None;
+            }
+
+            Some(UnusedArtifact {
+                folder: display_path(path.parent()?),
+                base_name: artifact_base_name(path.file_name()?.to_str()?),
+                size: metadata.len(),
+                idle_days,
+            })
+        })
+        .collect();
+
+    unused.sort_by_key(|a| std::cmp::Reverse(a.size));
+
+    log_msg!(
+        "[未使用构件] 发现 {} 个超过 {} 天未访问的 JAR",
+        "[Unused artifacts] Found {} JARs not accessed for {} days",
+        unused.len(),
+        days
+    );
+
+    Ok(unused)
+}
+
+/// 按 groupId 前缀统计仓库的磁盘占用，按大小降序返回
+///
+/// `depth` 为 groupId 保留的段数（默认 1），例如深度 2 时 `com/google/guava/...`
+/// 计入 `com.google`
+#[tauri::command]
+fn size_by_group(repo_path: String, depth: Option<usize>) -> Result<Vec<GroupSize>, String> {
+    let repo_path = Path::new(&repo_path);
+
+    ensure_repo_dir(repo_path)?;
+    let repo_path = &canonical_repo_path(repo_path);
+    let root = to_long_path(repo_path);
+    let depth = depth.unwrap_or(1).max(1);
+
+    let files = collect_repo_files(repo_path, |_| true);
+
+    let sizes: HashMap<String, (u64, usize)> = files
+        .par_iter()
+        .filter_map(|path| {
+            let size = fs::metadata(path).ok()?.len();
+            let relative = path.strip_prefix(&root).ok()?.parent()?;
+            let segments: Vec<_> = relative
+                .components()
+                .take(depth)
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            let group = if segments.is_empty() {
+                "(root)".to_string()
+            } else {
+                segments.join(".")
+            };
+            Some((group, size))
+        })
+        .fold(HashMap::new, |mut acc: HashMap<String, (u64, usize)>, (group, size)| {
+            let entry = acc.entry(group).or_insert((0, 0));
+            entry.0 += size;
+            entry.1 += 1;
+            acc
+        })
+        .reduce(HashMap::new, |mut acc, other| {
+            for (group, (size, count)) in other {
+                let entry = acc.entry(group).or_insert((0, 0));
+                entry.0 += size;
+                entry.1 += count;
+            }
+            acc
+        });
+
+    let mut groups: Vec<GroupSize> = sizes
+        .into_iter()
+        .map(|(group, (size, file_count))| GroupSize {
+            group,
+            size,
+            file_count,
+        })
+        .collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.size));
+
+    log_msg!(
+        "[空间统计] 共 {} 个文件，{} 个 groupId 前缀",
+        "[Size by group] {} files in {} groupId prefixes",
+        files.len(),
+        groups.len()
+    );
+
+    Ok(groups)
+}
+
+/// 按 groupId 统计构件修改时间的范围，按最新修改时间升序返回，最久未更新的 group 排在前面
+///
+/// 只读统计，遍历一次仓库；不符合 `<groupId>/<artifactId>/<version>/` 布局的文件不计入
+#[tauri::command]
+fn group_age_report(repo_path: String) -> Result<Vec<GroupAge>, String> {
+    let repo_path = Path::new(&repo_path);
+
+    ensure_repo_dir(repo_path)?;
+    let repo_path = &canonical_repo_path(repo_path);
+    let root = to_long_path(repo_path);
+
+    let files = collect_artifact_files(repo_path, &ArtifactType::ALL);
+
+    let ages: HashMap<String, (u64, u64, usize)> = files
+        .par_iter()
+        .filter_map(|path| {
+            let mtime = fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            let segments: Vec<_> = path
+                .strip_prefix(&root)
+                .ok()?
+                .parent()?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            // 最后两段为 artifactId 和版本
+            if segments.len() < 3 {
+                return None;
+            }
+            Some((segments[..segments.len() - 2].join("."), mtime))
+        })
+        .fold(HashMap::new, |mut acc: HashMap<String, (u64, u64, usize)>, (group, mtime)| {
+            let entry = acc.entry(group).or_insert((mtime, mtime, 0));
+            entry.0 = entry.0.min(mtime);
+            entry.1 = entry.1.max(mtime);
+            entry.2 += 1;
+            acc
+        })
+        .reduce(HashMap::new, |mut acc, other| {
+            for (group, (oldest, newest, count)) in other {
+                let entry = acc.entry(group).or_insert((oldest, newest, 0));
+                entry.0 = entry.0.min(oldest);
+                entry.1 = entry.1.max(newest);
+                entry.2 += count;
+            }
+            acc
+        });
+
+    let mut groups: Vec<GroupAge> = ages
+        .into_iter()
+        .map(|(group, (oldest_mtime, newest_mtime, artifact_count))| GroupAge {
+            group,
+            oldest_mtime,
+            newest_mtime,
+            artifact_count,
+        })
+        .collect();
+    groups.sort_by_key(|g| g.newest_mtime);
+
+    log_msg!(
+        "[新旧统计] 共 {} 个构件文件，{} 个 groupId",
+        "[Group age] {} artifact files in {} groupIds",
+        files.len(),
+        groups.len()
+    );
+
+    Ok(groups)
+}
+
+/// 查找仅大小写不同的文件路径
+///
+/// 从区分大小写的来源同步到 Windows / macOS 后，这类文件会互相覆盖导致解析出错
+#[tauri::command]
+fn scan_case_collisions(repo_path: String) -> Result<Vec<CaseCollision>, String> {
+    let repo_path = Path::new(&repo_path);
+
+    ensure_repo_dir(repo_path)?;
+    let repo_path = &canonical_repo_path(repo_path);
+
+    let files = collect_repo_files(repo_path, |_| true);
+
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for path in &files {
+        let display = display_path(path);
+        groups.entry(display.to_lowercase()).or_default().push(display);
+    }
+
+    let mut collisions: Vec<CaseCollision> = groups
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(|mut paths| {
+            paths.sort();
+            CaseCollision { paths }
+        })
+        .collect();
+    collisions.sort_by(|a, b| a.paths.cmp(&b.paths));
+
+    log_msg!(
+        "[大小写冲突] 检查了 {} 个文件，发现 {} 组仅大小写不同的路径",
+        "[Case collisions] Checked {} files, found {} groups differing only in case",
+        files.len(),
+        collisions.len()
+    );
+
+    Ok(collisions)
+}
+
+/// 统计各远程仓库提供的构件数，并标出其中损坏构件的来源
+///
+/// `invalid` 通常传入上一次扫描得到的损坏构件，用于定位"是哪个代理在返回垃圾"
+#[tauri::command]
+fn scan_remote_sources(
+    repo_path: String,
+    invalid: Option<Vec<CleanItem>>,
+) -> Result<Vec<RemoteSourceStat>, String> {
+    let repo_path = Path::new(&repo_path);
+
+    ensure_repo_dir(repo_path)?;
+    let repo_path = &canonical_repo_path(repo_path);
+
+    // 按目录归类损坏构件的基础名，便于与 _remote.repositories 中的文件名匹配
+    let mut invalid_by_folder: HashMap<String, Vec<String>> = HashMap::new();
+    for item in invalid.unwrap_or_default() {
+        invalid_by_folder
+            .entry(item.folder)
+            .or_default()
+            .push(item.base_name);
+    }
+
+    let files = collect_repo_files(repo_path, |name| name == "_remote.repositories");
+
+    let per_file: Vec<Vec<(String, bool)>> = files
+        .par_iter()
+        .filter_map(|path| {
+            let content = fs::read_to_string(path).ok()?;
+            let folder = display_path(path.parent()?);
+            let invalid_names = invalid_by_folder.get(&folder);
+
+            Some(
+                parse_remote_repositories(&content)
+                    .into_iter()
+                    .map(|(file_name, remote_id)| {
+                        let is_invalid = invalid_names
+                            .map(|names| names.iter().any(|n| belongs_to_artifact(&file_name, n)))
+                            .unwrap_or(false);
+                        (remote_id, is_invalid)
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+
+    let mut stats: HashMap<String, RemoteSourceStat> = HashMap::new();
+    for (remote_id, is_invalid) in per_file.into_iter().flatten() {
+        let stat = stats
+            .entry(remote_id.clone())
+            .or_insert_with(|| RemoteSourceStat {
+                remote_id,
+                artifact_count: 0,
+                invalid_count: 0,
+            });
+        stat.artifact_count += 1;
+        if is_invalid {
+            stat.invalid_count += 1;
+        }
+    }
+
+    let mut stats: Vec<RemoteSourceStat> = stats.into_values().collect();
+    stats.sort_by(|a, b| {
+        b.invalid_count
+            .cmp(&a.invalid_count)
+            .then(b.artifact_count.cmp(&a.artifact_count))
+    });
+
+    log_msg!(
+        "[来源统计] 解析了 {} 个 _remote.repositories，共 {} 个远程仓库",
+        "[Remote sources] Parsed {} _remote.repositories files, {} remotes",
+        files.len(),
+        stats.len()
+    );
+
+    Ok(stats)
+}
+
+/// 扫描 Gradle 缓存（`~/.gradle/caches/modules-2`）中损坏的 JAR/POM
+///
+/// Gradle 布局为 `files-2.1/<group>/<module>/<version>/<sha1>/<文件>`，返回的目录指向
+/// sha1 子目录。同一模块的 JAR 与 POM 分处不同的 sha1 目录，依赖 Maven 布局的检查
+/// （MANIFEST 坐标、缺少主 JAR、父 POM）会被关闭
+#[tauri::command]
+async fn scan_gradle_cache(
+    state: State<'_, AppState>,
+    cache_path: String,
+    config: Option<ScanConfig>,
+) -> Result<ScanReport, String> {
+    // 与仓库扫描共用暂停状态；不影响缓存的最近一次仓库扫描结果
+    let pause = Arc::clone(&state.scan_pause);
+    pause.set_paused(false);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let config = ScanConfig {
+            check_manifest: false,
+            check_missing_jar: false,
+            check_parent_pom: false,
+            check_version_mismatch: false,
+            ..config.unwrap_or_default()
+        };
+        run_gradle_scan(Path::new(&cache_path), config, &pause)
+    })
+    .await
+    .map_err(|e| format!("扫描任务失败: {}", e))?
+}
+
+/// `scan_gradle_cache` 的同步实现，暂停期间工作线程阻塞等待
+fn run_gradle_scan(
+    cache_path: &Path,
+    config: ScanConfig,
+    pause: &ScanPause,
+) -> Result<ScanReport, String> {
+    ensure_repo_dir(cache_path)?;
+    let cache_path = &canonical_repo_path(cache_path);
+    let scan_started = Instant::now();
+
+    // 与仓库扫描相同：文件总数超过上限时拒绝扫描，需显式传入 force
+    let max_files = if config.force {
+        usize::MAX
+    } else {
+        config.max_files
+    };
+    let files = collect_repo_files_guarded(cache_path, max_files, |name| {
+        is_configured_artifact(name, &config)
+    })?;
+    let collect_ms = scan_started.elapsed().as_millis() as u64;
+    let check_started = Instant::now();
+
+    let (pool, thread_count) = build_scan_pool(&config)?;
+    let limits = ScanLimits::new(&config);
+    let mut invalid_artifacts: Vec<InvalidArtifact> = pool.install(|| {
+        files
+            .par_iter()
+            .filter_map(|path| {
+                pause.wait_while_paused();
+                check_file(path, None, &config, &limits)
+            })
+            .collect()
+    });
+
+    let reason_counts = count_reasons(&invalid_artifacts);
+    let total_invalid = invalid_artifacts.len();
+    let truncated = cap_results(&mut invalid_artifacts, config.max_results);
+
+    let check_ms = check_started.elapsed().as_millis() as u64;
+    let duration_ms = scan_started.elapsed().as_millis() as u64;
+
+    log_msg!(
+        "[Gradle 缓存扫描] 共检查 {} 个文件,发现 {} 个损坏的构件,耗时 {} ms",
+        "[Gradle cache scan] Checked {} files, {} invalid artifacts found in {} ms",
+        files.len(),
+        total_invalid,
+        duration_ms
+    );
+
+    Ok(ScanReport {
+        reason_counts,
+        artifacts: invalid_artifacts,
+        files_scanned: files.len(),
+        duration_ms,
+        collect_ms,
+        check_ms,
+        thread_count,
+        total_invalid,
+        truncated,
+    })
+}
+
+#[tauri::command]
+fn scan_archive(archive_path: String, config: Option<ScanConfig>) -> Result<ScanReport, String> {
+    let config = config.unwrap_or_default();
+    let scan_started = Instant::now();
+
+    let file = fs::File::open(&archive_path)
+        .map_err(|e| format!("无法打开归档文件 {}: {}", archive_path, e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("无法解析归档文件 {}: {}", archive_path, e))?;
+
+    let mut invalid_artifacts = Vec::new();
+    let mut files_scanned = 0;
+
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(e) => {
+                log_msg!(
+                    "[归档扫描] 无法读取第 {} 个条目: {}",
+                    "[Archive scan] Cannot read entry #{}: {}",
+                    i,
+                    e
+                );
+                continue;
+            }
+        };
+
+        if !entry.is_file() {
+            continue;
+        }
+
+        let entry_name = entry.name().to_string();
+        let file_name = entry_name
+            .rsplit('/')
+            .next()
+            .unwrap_or(&entry_name)
+            .to_string();
+        if !is_configured_artifact(&file_name, &config) {
+            continue;
+        }
+
+        // 直接在内存中读取条目内容，不解压到磁盘
+        let mut bytes = Vec::with_capacity(entry.size().min(16 * 1024 * 1024) as usize);
+        if let Err(e) = entry.read_to_end(&mut bytes) {
+            log_msg!(
+                "[归档扫描] 读取条目失败 {}: {}",
+                "[Archive scan] Failed to read entry {}: {}",
+                entry_name,
+                e
+            );
+            continue;
+        }
+        files_scanned += 1;
+
+        let location = Path::new(&entry_name);
+        if let Some(finding) = classify_artifact(location, ArtifactInput::Bytes(&bytes), &config) {
+            let parent = entry_name
+                .rsplit_once('/')
+                .map(|(dir, _)| dir)
+                .unwrap_or_default();
+            invalid_artifacts.push(InvalidArtifact::new(
+                format!("{}!/{}", archive_path, parent),
+                artifact_base_name(&file_name),
+                finding,
+            ));
+        }
+    }
+
+    let duration_ms = scan_started.elapsed().as_millis() as u64;
+    log_msg!(
+        "[归档扫描] 扫描完成,共检查 {} 个条目,发现 {} 个损坏的构件",
+        "[Archive scan] Done, checked {} entries, {} invalid artifacts found",
+        files_scanned,
+        invalid_artifacts.len()
+    );
+
+    let reason_counts = count_reasons(&invalid_artifacts);
+    let total_invalid = invalid_artifacts.len();
+    let truncated = cap_results(&mut invalid_artifacts, config.max_results);
+
+    Ok(ScanReport {
+        reason_counts,
+        artifacts: invalid_artifacts,
+        files_scanned,
+        duration_ms,
+        collect_ms: 0,
+        check_ms: duration_ms,
+        // 归档条目只能顺序读取
+        thread_count: 1,
+        total_invalid,
+        truncated,
+    })
+}
+
+#[tauri::command]
+async fn verify_against_remote(
+    repo_path: String,
+    base_url: String,
+) -> Result<RemoteVerifyResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo_path = Path::new(&repo_path);
+
+        ensure_repo_dir(repo_path)?;
+        let repo_path = &canonical_repo_path(repo_path);
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REMOTE_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+        // 使用独立的小线程池限制并发请求数，避免压垮远程仓库
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(REMOTE_VERIFY_CONCURRENCY)
+            .build()
+            .map_err(|e| format!("创建线程池失败: {}", e))?;
+
+        let root = to_long_path(repo_path);
+        let files = collect_artifact_files(repo_path, &ArtifactType::ALL);
+        log_msg!(
+            "[远程校验] 共 {} 个 JAR/POM 文件，远程地址: {}",
+            "[Remote verify] {} JAR/POM files, remote: {}",
+            files.len(),
+            base_url
+        );
+
+        let checks: Vec<(PathBuf, RemoteCheck)> = pool.install(|| {
+            files
+                .into_par_iter()
+                .map(|path| {
+                    let check = check_against_remote(&client, &base_url, &root, &path);
+                    (path, check)
+                })
+                .collect()
+        });
+
+        let mut result = RemoteVerifyResult {
+            mismatches: Vec::new(),
+            checked_count: 0,
+            not_found_count: 0,
+            errors: Vec::new(),
+        };
+
+        for (path, check) in checks {
+            match check {
+                RemoteCheck::Match => result.checked_count += 1,
+                RemoteCheck::Mismatch => {
+                    result.checked_count += 1;
+                    let file_name = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or_default();
+                    result.mismatches.push(InvalidArtifact::new(
+                        path.parent().map(display_path).unwrap_or_default(),
+                        artifact_base_name(file_name),
+                        Finding::new(Reason::ChecksumMismatch),
+                    ));
+                }
+                RemoteCheck::NotFound => result.not_found_count += 1,
+                RemoteCheck::Failed(e) => result.errors.push(e),
+            }
+        }
+
+        log_msg!(
+            "[远程校验] 完成，已校验 {} 个，不一致 {} 个，远程不存在 {} 个",
+            "[Remote verify] Done, {} checked, {} mismatched, {} not on remote",
+            result.checked_count,
+            result.mismatches.len(),
+            result.not_found_count
+        );
+
+        Ok(result)
+    })
+    .await
+    .map_err(|e| format!("远程校验任务失败: {}", e))?
+}
+
+/// 执行删除操作，遇到文件被短暂占用导致的错误时等待后重试，最多尝试 `attempts` 次
+fn remove_with_retry<F>(mut remove: F, attempts: u32) -> std::io::Result<()>
+where
+    F: FnMut() -> std::io::Result<()>,
+{
+    let attempts = attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match remove() {
+            Err(e)
+                if attempt < attempts
+                    && matches!(
+                        e.kind(),
+                        std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::Other
+                    ) =>
+            {
+                std::thread::sleep(DELETE_RETRY_BASE_DELAY * attempt);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 生成删除指定构件的脚本而不直接删除，适用于只读挂载的仓库快照：
+/// 用户检查后可在有写权限的环境中执行
+///
+/// 删除范围与 `clean_artifacts` 的默认行为一致：属于该构件的文件及目录中的元数据文件；
+/// 锁文件等单个文件条目只删除该文件
+#[tauri::command]
+fn generate_cleanup_script(items: Vec<CleanItem>, platform: ScriptPlatform) -> String {
+    let mut script = String::from(platform.header());
+    let mut file_count = 0;
+
+    for item in items {
+        let folder = to_long_path(Path::new(&item.folder));
+        let with_metadata = is_artifact_base_name(&item.base_name);
+        let mut file_names: Vec<String> = match fs::read_dir(&folder) {
+            Ok(entries) => entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                .filter(|name| {
+                    (with_metadata && METADATA_FILES.contains(&name.as_str()))
+                        || belongs_to_artifact(name, &item.base_name)
+                })
+                .collect(),
+            Err(e) => {
+                script.push_str(&platform.comment(&format!(
+                    "无法读取目录 {}: {}",
+                    item.folder, e
+                )));
+                script.push('\n');
+                continue;
+            }
+        };
+        file_names.sort();
+
+        script.push('\n');
+        script.push_str(&platform.comment(&format!("{} ({})", item.base_name, item.folder)));
+        script.push('\n');
+        for name in file_names {
+            let path = display_path(&Path::new(&item.folder).join(&name));
+            script.push_str(&platform.remove_command(&path));
+            script.push('\n');
+            file_count += 1;
+        }
+    }
+
+    log_msg!(
+        "[清理脚本] 生成了 {} 条删除命令",
+        "[Cleanup script] Generated {} remove commands",
+        file_count
+    );
+
+    script
+}
+
+/// 规范化待删除的目录并确认其位于仓库内，防止误删仓库之外的内容
+///
+/// 目录已不存在时返回 `Ok(None)`
+fn resolve_folder_in_repo(folder: &str, repo_root: &Path) -> Result<Option<PathBuf>, String> {
+    let long_folder = to_long_path(Path::new(folder));
+    if !long_folder.exists() {
+        return Ok(None);
+    }
+
+    match fs::canonicalize(&long_folder) {
+        Ok(f) if f.starts_with(repo_root) => Ok(Some(f)),
+        Ok(_) => Err(format!("拒绝删除仓库之外的目录: {}", folder)),
+        Err(e) => Err(format!("无法解析目录 {}: {}", folder, e)),
+    }
+}
+
+/// 删除指定构件的校验和文件（`.sha1`、`.md5`、`.sha256` 等），构件本身保留
+///
+/// 用于手动修复构件后让 Maven 重新生成或下载校验和
+#[tauri::command]
+fn purge_checksums(items: Vec<CleanItem>, repo_root: String) -> Result<PurgeResult, String> {
+    ensure_repo_dir(Path::new(&repo_root))?;
+    let repo_root = fs::canonicalize(to_long_path(Path::new(&repo_root)))
+        .map_err(|e| format!("无法解析仓库路径 {}: {}", repo_root, e))?;
+
+    let mut checksums_deleted = 0;
+    let mut errors = Vec::new();
+
+    for item in items {
+        let folder = match resolve_folder_in_repo(&item.folder, &repo_root) {
+            Ok(Some(folder)) => folder,
+            Ok(None) => continue,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        let entries = match fs::read_dir(&folder) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(format!("无法读取目录 {}: {}", folder.display(), e));
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let file_path = entry.path();
+            let is_checksum = file_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(checksum_sidecar)
+                .is_some_and(|(artifact, _)| belongs_to_artifact(artifact, &item.base_name));
+            if !is_checksum {
+                continue;
+            }
+
+            match fs::remove_file(&file_path) {
+                Ok(_) => checksums_deleted += 1,
+                Err(e) => errors.push(format!("删除失败 {}: {}", file_path.display(), e)),
+            }
+        }
+    }
+
+    log_msg!(
+        "[清理校验和] 删除了 {} 个校验和文件",
+        "[Purge checksums] Deleted {} checksum files",
+        checksums_deleted
+    );
+
+    Ok(PurgeResult {
+        checksums_deleted,
+        errors,
+    })
+}
+
+#[tauri::command]
+fn clean_artifacts(
+    items: Vec<CleanItem>,
+    repo_root: String,
+    options: Option<CleanOptions>,
+) -> Result<CleanResult, String> {
+    let options = options.unwrap_or_default();
+    ensure_repo_dir(Path::new(&repo_root))?;
+    let repo_root = fs::canonicalize(to_long_path(Path::new(&repo_root)))
+        .map_err(|e| format!("无法解析仓库路径 {}: {}", repo_root, e))?;
+
+    let mut artifacts_deleted = 0;
+    let mut metadata_deleted = 0;
+    let mut errors = Vec::new();
+
+    'items: for item in items {
+        let folder = match resolve_folder_in_repo(&item.folder, &repo_root) {
+            Ok(Some(folder)) => folder,
+            Ok(None) => continue,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        let folder = folder.as_path();
+        // 锁文件等单个文件条目只删除该文件，不动目录中其他构件的元数据
+        let is_artifact = is_artifact_base_name(&item.base_name);
+        let delete_metadata = options.delete_metadata && is_artifact;
+
+        if options.remove_whole_version_dir && is_artifact {
+            // 需要保留元数据时，不能整目录删除
+            let removable = version_dir_file_count(folder, &item.base_name)
+                .filter(|(_, metadata_count)| options.delete_metadata || *metadata_count == 0);
+            if let Some((artifact_count, metadata_count)) = removable {
+                match remove_with_retry(|| fs::remove_dir_all(folder), options.delete_attempts) {
+                    Ok(_) => {
+                        artifacts_deleted += artifact_count;
+                        metadata_deleted += metadata_count;
+                    }
+                    Err(e) => {
+                        errors.push(format!("删除目录失败 {}: {}", folder.display(), e));
+                        if options.fail_fast {
+                            break 'items;
+                        }
+                    }
+                }
+                continue;
+            }
+        }
+
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(format!("无法读取目录 {}: {}", folder.display(), e));
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let file_path = entry.path();
+            let file_name = match file_path.file_name().and_then(|n| n.to_str()) {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let is_metadata = METADATA_FILES.contains(&file_name);
+            let should_delete = if is_metadata {
+                delete_metadata
+            } else {
+                belongs_to_artifact(file_name, &item.base_name)
+            };
+
+            if should_delete {
+                match remove_with_retry(|| fs::remove_file(&file_path), options.delete_attempts) {
+                    Ok(_) if is_metadata => metadata_deleted += 1,
+                    Ok(_) => artifacts_deleted += 1,
+                    Err(e) => {
+                        errors.push(format!("删除失败 {}: {}", file_path.display(), e));
+                        if options.fail_fast {
+                            break 'items;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(CleanResult {
+        artifacts_deleted,
+        metadata_deleted,
+        errors,
+    })
+}
+
+/// 监视线程主循环：收集新写入的构件，写入稳定后逐个检查，损坏时交给 `on_invalid`
+///
+/// 监视器被丢弃时事件通道断开，循环随之退出
+fn run_watch_loop(
+    events: Receiver<notify::Result<notify::Event>>,
+    repo_root: PathBuf,
+    config: ScanConfig,
+    mut on_invalid: impl FnMut(InvalidArtifact),
+) {
+    let limits = ScanLimits::new(&config);
+    // 路径 -> (首次写入时间, 最近写入时间)
+    let mut pending: HashMap<PathBuf, (Instant, Instant)> = HashMap::new();
+
+    loop {
+        match events.recv_timeout(WATCH_POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                if !matches!(
+                    event.kind,
+                    notify::EventKind::Create(_) | notify::EventKind::Modify(_)
+                ) {
+                    continue;
+                }
+                let now = Instant::now();
+                for path in event.paths {
+                    let is_artifact = path
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|name| {
+                            is_configured_artifact(name, &config)
+                                && !is_temp_download_file(name)
+                        })
+                        .unwrap_or(false);
+                    if is_artifact {
+                        pending
+                            .entry(path)
+                            .and_modify(|(_, last)| *last = now)
+                            .or_insert((now, now));
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                log_msg!("[仓库监视] 监视出错: {}", "[Repo watch] Watch error: {}", e);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let now = Instant::now();
+        let ready: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, (first, last))| {
+                now.duration_since(*last) >= WATCH_DEBOUNCE
+                    || now.duration_since(*first) >= WATCH_MAX_DELAY
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in ready {
+            pending.remove(&path);
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(invalid) = check_file(&path, Some(&repo_root), &config, &limits) {
+                log_msg!(
+                    "[仓库监视] 发现损坏的构件: {}",
+                    "[Repo watch] Invalid artifact found: {}",
+                    display_path(&path)
+                );
+                on_invalid(invalid);
+            }
+        }
+    }
+
+    log_msg!("[仓库监视] 已停止", "[Repo watch] Stopped");
+}
+
+/// 监视仓库目录，新写入的 JAR/POM 稳定后按扫描配置检查，损坏时发送 `invalid-found` 事件
+///
+/// 已在监视时会先停止旧的监视
+#[tauri::command]
+fn start_repo_watch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    repo_path: String,
+    config: Option<ScanConfig>,
+) -> Result<(), String> {
+    let repo_path = Path::new(&repo_path);
+    ensure_repo_dir(repo_path)?;
+    let repo_root = to_long_path(&canonical_repo_path(repo_path));
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        sender.send(event).ok();
+    })
+    .map_err(|e| format!("创建文件监视失败: {}", e))?;
+    watcher
+        .watch(&repo_root, notify::RecursiveMode::Recursive)
+        .map_err(|e| format!("无法监视目录 {}: {}", display_path(&repo_root), e))?;
+
+    let config = config.unwrap_or_default();
+    let root = repo_root.clone();
+    std::thread::spawn(move || {
+        run_watch_loop(receiver, root, config, |invalid| {
+            if let Err(e) = app.emit(INVALID_FOUND_EVENT, invalid) {
+                log_msg!(
+                    "[仓库监视] 发送事件失败: {}",
+                    "[Repo watch] Failed to emit event: {}",
+                    e
+                );
+            }
+        })
+    });
+
+    *state
+        .repo_watch
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))? = Some(watcher);
+
+    log_msg!(
+        "[仓库监视] 开始监视: {}",
+        "[Repo watch] Watching: {}",
+        display_path(&repo_root)
+    );
+    Ok(())
+}
+
+/// 暂停正在进行的扫描，已开始检查的文件会先完成
+#[tauri::command]
+fn pause_scan(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    set_scan_paused(&state, true, |event, paused| {
+        app.emit(event, paused).map_err(|e| e.to_string())
+    })
+}
+
+/// 恢复被暂停的扫描，未暂停时不做任何事
+#[tauri::command]
+fn resume_scan(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    set_scan_paused(&state, false, |event, paused| {
+        app.emit(event, paused).map_err(|e| e.to_string())
+    })
+}
+
+/// 切换暂停状态，并通过 `emit` 发送一次 `scan-pause-changed` 事件
+///
+/// 状态已切换后发送失败只记录日志，不把暂停 / 恢复本身报告为失败
+fn set_scan_paused(
+    state: &AppState,
+    paused: bool,
+    emit: impl FnOnce(&str, bool) -> Result<(), String>,
+) -> Result<(), String> {
+    if state.scan_pause.is_paused() == paused {
+        return Ok(());
+    }
+    state.scan_pause.set_paused(paused);
+    if paused {
+        log_msg!("[多线程扫描] 已暂停", "[Parallel scan] Paused");
+    } else {
+        log_msg!("[多线程扫描] 已恢复", "[Parallel scan] Resumed");
+    }
+    if let Err(e) = emit(SCAN_PAUSE_EVENT, paused) {
+        log_msg!(
+            "[多线程扫描] 发送暂停状态事件失败: {}",
+            "[Parallel scan] Failed to emit pause state event: {}",
+            e
+        );
+    }
+    Ok(())
+}
+
+/// 停止仓库监视，未在监视时不做任何事
+#[tauri::command]
+fn stop_repo_watch(state: State<'_, AppState>) -> Result<(), String> {
+    *state
+        .repo_watch
+        .lock()
+        .map_err(|e| format!("获取状态锁失败: {}", e))? = None;
+    Ok(())
+}
+
+// ===================== 应用入口 =====================
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_fs::init())
+        .manage(AppState::default())
+        .invoke_handler(tauri::generate_handler![
+            get_maven_repo_path,
+            set_repo_path_override,
+            clear_repo_path_override,
+            write_local_repository,
+            scan_invalid_artifacts,
+            pause_scan,
+            resume_scan,
+            get_last_scan_result,
+            diff_scan_reports,
+            check_single_artifact,
+            scan_unused_artifacts,
+            scan_remote_sources,
+            size_by_group,
+            group_age_report,
+            scan_case_collisions,
+            scan_gradle_cache,
+            scan_archive,
+            verify_against_remote,
+            scan_and_clean,
+            purge_checksums,
+            start_repo_watch,
+            stop_repo_watch,
+            generate_cleanup_script,
+            clean_artifacts
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+// ===================== 测试 =====================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Harbor 登录页被代理缓存成 POM 时的典型内容
+    const HARBOR_POM: &str = "<!DOCTYPE html>\n<html>\n<head><title>Harbor</title></head>\n<body>Login to Harbor</body>\n</html>\n";
+    const VALID_POM: &str = "<?xml version=\"1.0\"?>\n<project>\n  <groupId>com.example</groupId>\n  <artifactId>foo</artifactId>\n  <version>1.0</version>\n  <packaging>pom</packaging>\n</project>\n";
+
+    /// 在内存中按顺序写入条目（不压缩，大小可预期），返回 ZIP 内容
+    fn zip_bytes(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// 带清单和一个类文件、大于默认大小下限的有效 JAR
+    fn valid_jar() -> Vec<u8> {
+        zip_bytes(&[
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\r\n\r\n"),
+            ("com/example/Foo.class", &[0xca; 2048]),
+        ])
+    }
+
+    fn reason_of(finding: Option<Finding>) -> Option<Reason> {
+        finding.map(|f| f.reason)
+    }
+
+    /// 测试用的临时 Maven 仓库，目录名包含 `repository` 以通过扫描前的路径检查，离开作用域时删除
+    struct TempRepo {
+        dir: PathBuf,
+        root: PathBuf,
+    }
+
+    impl TempRepo {
+        fn new() -> Self {
+            static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "mavengo-test-{}-{}",
+                std::process::id(),
+                NEXT_ID.fetch_add(1, Ordering::Relaxed)
+            ));
+            let root = dir.join("repository");
+            fs::create_dir_all(&root).unwrap();
+            Self { dir, root }
+        }
+
+        fn path(&self) -> &Path {
+            &self.root
+        }
+
+        fn path_string(&self) -> String {
+            self.root.to_string_lossy().into_owned()
+        }
+
+        /// 在仓库中写入文件（自动创建上级目录），返回完整路径
+        fn write(&self, relative: &str, content: impl AsRef<[u8]>) -> PathBuf {
+            let path = self.root.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            fs::remove_dir_all(to_long_path(&self.dir)).ok();
+        }
+    }
+
+    /// 同时设置文件的访问时间和修改时间
+    fn set_file_times(path: &Path, time: SystemTime) {
+        let times = fs::FileTimes::new().set_accessed(time).set_modified(time);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_times(times)
+            .unwrap();
+    }
+
+    /// 在本地端口上提供固定响应的 HTTP 服务，返回基础 URL
+    ///
+    /// `routes` 为 请求路径 -> (状态码, 响应体)，未登记的路径返回 404
+    fn serve_http(routes: HashMap<String, (u16, String)>) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = routes
+                    .get(path)
+                    .cloned()
+                    .unwrap_or((404, "Not Found".to_string()));
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).ok();
+            }
+        });
+        base_url
+    }
+
+    #[test]
+    fn classify_flags_small_non_zip_jar() {
+        let config = ScanConfig::default();
+        let finding = classify_artifact(
+            Path::new("foo-1.0.jar"),
+            ArtifactInput::Bytes(b"not a jar"),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::CorruptZip));
+    }
+
+    #[test]
+    fn classify_accepts_valid_jar() {
+        let config = ScanConfig::default();
+        let jar = valid_jar();
+        let finding = classify_artifact(
+            Path::new("foo-1.0.jar"),
+            ArtifactInput::Bytes(&jar),
+            &config,
+        );
+        assert_eq!(reason_of(finding), None);
+    }
+
+    #[test]
+    fn classify_flags_harbor_pom() {
+        let config = ScanConfig::default();
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(HARBOR_POM.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::HarborPom));
+    }
+
+    #[test]
+    fn classify_accepts_valid_pom() {
+        let config = ScanConfig::default();
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(VALID_POM.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), None);
+    }
+
+    #[test]
+    fn override_path_skips_detection() {
+        let repo = TempRepo::new();
+        let state = AppState::default();
+        *state.repo_path_override.lock().unwrap() = Some(repo.path_string());
+
+        let (location, log) =
+            with_log_capture(|| detect_repo_location(&state, None, &isolated_detect_env()));
+        let location = location.unwrap();
+        assert_eq!(location.source, RepoSource::Override);
+        assert_eq!(Path::new(&location.path), repo.path());
+        assert!(!location.maven_found);
+        // 只有一行覆盖路径的日志，没有进入任何检测步骤
+        assert_eq!(log.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_with_timeout_returns_output() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo hello"]);
+        let output = run_with_timeout(&mut command, Duration::from_secs(10)).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_with_timeout_kills_slow_command() {
+        let repo = TempRepo::new();
+        let pid_file = repo.path().join("pid");
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("echo $$ > '{}'; exec sleep 30", pid_file.display()));
+
+        let started = Instant::now();
+        let result = run_with_timeout(&mut command, Duration::from_secs(1));
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // 超时后子进程已被杀掉并回收
+        let pid = fs::read_to_string(&pid_file).unwrap();
+        let alive = Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!alive.success());
+    }
+
+    #[test]
+    fn abnormal_mtime_is_flagged() {
+        let repo = TempRepo::new();
+        let pom = repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+        let config = ScanConfig {
+            check_mtime: true,
+            ..ScanConfig::default()
+        };
+        let classify = || reason_of(classify_artifact(&pom, ArtifactInput::File(&pom), &config));
+        assert_eq!(classify(), None);
+
+        set_file_times(
+            &pom,
+            SystemTime::now() + Duration::from_secs(365 * 24 * 60 * 60),
+        );
+        assert_eq!(classify(), Some(Reason::AbnormalMtime));
+
+        set_file_times(&pom, UNIX_EPOCH + Duration::from_secs(24 * 60 * 60));
+        assert_eq!(classify(), Some(Reason::AbnormalMtime));
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn long_windows_paths_are_scanned_and_cleaned() {
+        let repo = TempRepo::new();
+        let deep: PathBuf = (0..12)
+            .map(|i| format!("segment-{:02}-{}", i, "x".repeat(20)))
+            .collect();
+        let folder = repo.path().join(deep).join("foo").join("1.0");
+        let jar = folder.join("foo-1.0.jar");
+        assert!(jar.as_os_str().len() > 260);
+        fs::create_dir_all(to_long_path(&folder)).unwrap();
+        fs::write(to_long_path(&jar), b"not a jar").unwrap();
+
+        let report = run_scan(repo.path(), ScanConfig::default(), &ScanPause::default()).unwrap();
+        assert_eq!(report.artifacts.len(), 1);
+        assert_eq!(report.artifacts[0].reason, Reason::CorruptZip);
+        assert!(!report.artifacts[0].folder.starts_with(r"\\?\"));
+
+        let items = report
+            .artifacts
+            .iter()
+            .map(|a| CleanItem {
+                folder: a.folder.clone(),
+                base_name: a.base_name.clone(),
+            })
+            .collect();
+        let result = clean_artifacts(items, repo.path_string(), None).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.artifacts_deleted, 1);
+        assert!(!to_long_path(&jar).exists());
+    }
+
+    #[test]
+    fn check_against_remote_compares_sha1() {
+        let repo = TempRepo::new();
+        let matching = repo.write("com/example/foo/1.0/foo-1.0.jar", "foo content");
+        let mismatched = repo.write("com/example/bar/1.0/bar-1.0.jar", "bar content");
+        let missing = repo.write("com/example/baz/1.0/baz-1.0.jar", "baz content");
+        let failing = repo.write("com/example/qux/1.0/qux-1.0.jar", "qux content");
+        let sha1 = compute_sha1(&matching).unwrap();
+        let base_url = serve_http(HashMap::from([
+            (
+                "/com/example/foo/1.0/foo-1.0.jar.sha1".to_string(),
+                (200, format!("{}  foo-1.0.jar\n", sha1)),
+            ),
+            (
+                "/com/example/bar/1.0/bar-1.0.jar.sha1".to_string(),
+                (200, sha1),
+            ),
+            (
+                "/com/example/qux/1.0/qux-1.0.jar.sha1".to_string(),
+                (500, String::new()),
+            ),
+        ]));
+        let client = reqwest::blocking::Client::new();
+        let check = |path: &Path| check_against_remote(&client, &base_url, repo.path(), path);
+
+        assert!(matches!(check(&matching), RemoteCheck::Match));
+        assert!(matches!(check(&mismatched), RemoteCheck::Mismatch));
+        assert!(matches!(check(&missing), RemoteCheck::NotFound));
+        assert!(matches!(check(&failing), RemoteCheck::Failed(_)));
+    }
+
+    #[test]
+    fn pom_preview_bytes_controls_keyword_window() {
+        let mut pom = format!("<project>\n<!-- {} -->\n", "x".repeat(1985));
+        assert!(pom.len() >= 2000);
+        pom.push_str("<title>Harbor</title>\n</project>\n");
+
+        let classify = |pom_preview_bytes| {
+            let config = ScanConfig {
+                pom_preview_bytes,
+                ..ScanConfig::default()
+            };
+            reason_of(classify_artifact(
+                Path::new("foo-1.0.pom"),
+                ArtifactInput::Bytes(pom.as_bytes()),
+                &config,
+            ))
+        };
+        assert_eq!(classify(1024), None);
+        assert_eq!(classify(4096), Some(Reason::HarborPom));
+    }
+
+    #[test]
+    fn scan_report_counts_reasons() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"not a jar");
+        repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+        repo.write("com/example/bar/2.0/bar-2.0.jar", b"truncated");
+        repo.write("com/example/bar/2.0/bar-2.0.pom", HARBOR_POM);
+        repo.write("com/example/baz/3.0/baz-3.0.jar", valid_jar());
+        repo.write("com/example/baz/3.0/baz-3.0.jar.part", b"partial");
+
+        let report = run_scan(repo.path(), ScanConfig::default(), &ScanPause::default()).unwrap();
+        assert_eq!(report.total_invalid, 4);
+        assert_eq!(
+            report.reason_counts,
+            HashMap::from([
+                (Reason::CorruptZip, 2),
+                (Reason::HarborPom, 1),
+                (Reason::PartialDownload, 1),
+            ])
+        );
+        assert_eq!(count_reasons(&report.artifacts), report.reason_counts);
+    }
+
+    #[test]
+    fn clean_refuses_folders_outside_repo() {
+        let repo = TempRepo::new();
+        let outside = repo.path().parent().unwrap().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let victim = outside.join("foo-1.0.jar");
+        fs::write(&victim, b"keep me").unwrap();
+
+        // 直接指向仓库外，以及借助 `..` 跳出仓库
+        let items = vec![
+            CleanItem {
+                folder: outside.to_string_lossy().into_owned(),
+                base_name: "foo-1.0".to_string(),
+            },
+            CleanItem {
+                folder: repo
+                    .path()
+                    .join("..")
+                    .join("outside")
+                    .to_string_lossy()
+                    .into_owned(),
+                base_name: "foo-1.0".to_string(),
+            },
+        ];
+        let result = clean_artifacts(items, repo.path_string(), None).unwrap();
+        assert_eq!(result.errors.len(), 2);
+        assert_eq!(result.artifacts_deleted, 0);
+        assert!(victim.exists());
+    }
+
+    #[test]
+    fn pom_only_scan_skips_jars() {
+        let config = ScanConfig {
+            artifact_types: vec![ArtifactType::Pom],
+            ..ScanConfig::default()
+        };
+        assert!(!is_configured_artifact("foo-1.0.jar", &config));
+        assert!(is_configured_artifact("foo-1.0.pom", &config));
+
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.artifacts.len(), 1);
+        assert_eq!(report.artifacts[0].reason, Reason::HarborPom);
+        assert_eq!(report.artifacts[0].base_name, "foo-1.0");
+    }
+
+    #[test]
+    fn cleaning_single_file_items_keeps_metadata() {
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let lock = repo.write("com/example/foo/1.0/foo-1.0.jar.lock", b"");
+        let sha1 = repo.write("com/example/foo/1.0/foo-1.0.jar.sha1", b"");
+        let remote = repo.write(
+            "com/example/foo/1.0/_remote.repositories",
+            b"foo-1.0.jar>central=\n",
+        );
+        let folder = display_path(jar.parent().unwrap());
+        let items = || {
+            ["foo-1.0.jar.lock", "foo-1.0.jar.sha1"]
+                .iter()
+                .map(|base_name| CleanItem {
+                    folder: folder.clone(),
+                    base_name: base_name.to_string(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let script = generate_cleanup_script(items(), ScriptPlatform::Bash);
+        assert!(script.contains("foo-1.0.jar.lock"));
+        assert!(!script.contains("_remote.repositories"));
+
+        let options = CleanOptions {
+            remove_whole_version_dir: true,
+            ..CleanOptions::default()
+        };
+        let result = clean_artifacts(items(), repo.path_string(), Some(options)).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.artifacts_deleted, 2);
+        assert_eq!(result.metadata_deleted, 0);
+        assert!(!lock.exists());
+        assert!(!sha1.exists());
+        assert!(jar.exists());
+        assert!(remote.exists());
+    }
+
+    #[test]
+    fn whole_version_dir_removed_only_when_safe() {
+        let repo = TempRepo::new();
+        let options = CleanOptions {
+            remove_whole_version_dir: true,
+            ..CleanOptions::default()
+        };
+        let clean = |folder: &Path| {
+            let item = CleanItem {
+                folder: display_path(folder),
+                base_name: "foo-1.0".to_string(),
+            };
+            clean_artifacts(vec![item], repo.path_string(), Some(options.clone())).unwrap()
+        };
+
+        // 目录中只有该构件及元数据：整目录删除
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+        repo.write("com/example/foo/1.0/_remote.repositories", b"");
+        let folder = jar.parent().unwrap().to_path_buf();
+        assert_eq!(version_dir_file_count(&folder, "foo-1.0"), Some((2, 1)));
+        let result = clean(&folder);
+        assert_eq!((result.artifacts_deleted, result.metadata_deleted), (2, 1));
+        assert!(!folder.exists());
+
+        // 存在无关文件：拒绝整目录删除，只删除该构件的文件
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        let notes = repo.write("com/example/foo/1.0/notes.txt", b"keep");
+        assert_eq!(version_dir_file_count(&folder, "foo-1.0"), None);
+        let result = clean(&folder);
+        assert_eq!(result.artifacts_deleted, 1);
+        assert!(notes.exists());
+    }
+
+    #[test]
+    fn scan_report_includes_timing() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+
+        // 先暂停一段时间再恢复，保证扫描总耗时不为零
+        let pause = Arc::new(ScanPause::default());
+        pause.set_paused(true);
+        let resumer = {
+            let pause = Arc::clone(&pause);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                pause.set_paused(false);
+            })
+        };
+        let report = run_scan(repo.path(), ScanConfig::default(), &pause).unwrap();
+        resumer.join().unwrap();
+
+        assert_eq!(report.files_scanned, 2);
+        assert!(report.duration_ms > 0);
+        assert!(report.duration_ms >= report.collect_ms + report.check_ms);
+        assert!(report.thread_count > 0);
+    }
+
+    #[test]
+    fn maven_metadata_validity() {
+        let repo = TempRepo::new();
+        let content = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<metadata>\n  <groupId>com.example</groupId>\n  <artifactId>foo</artifactId>\n  <versioning>\n    <latest>1.0</latest>\n  </versioning>\n</metadata>\n";
+        let valid = repo.write("com/example/foo/maven-metadata-central.xml", content);
+        let truncated = repo.write(
+            "com/example/foo/maven-metadata.xml",
+            &content[..content.len() / 2],
+        );
+        let html = repo.write("com/example/bar/maven-metadata.xml", HARBOR_POM);
+
+        assert!(is_maven_metadata_file("maven-metadata-central.xml"));
+        assert!(is_valid_maven_metadata(&valid));
+        assert!(!is_valid_maven_metadata(&truncated));
+        assert!(!is_valid_maven_metadata(&html));
+    }
+
+    #[test]
+    fn log_line_picks_language() {
+        let count = 3;
+        let zh = || format!("[多线程扫描] 发现 {} 个文件", count);
+        let en = || format!("[Parallel scan] Found {} files", count);
+        assert_eq!(log_line(zh, en, true), "[Parallel scan] Found 3 files");
+        assert_eq!(log_line(zh, en, false), "[多线程扫描] 发现 3 个文件");
+    }
+
+    #[test]
+    fn classify_flags_nexus_error_pom() {
+        let config = ScanConfig::default();
+        let nexus = "<!-- Nexus NX-1234 -->\n<html>\n<head><title>404 - Path not found</title></head>\n</html>\n";
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(nexus.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::NexusError));
+
+        let config = ScanConfig {
+            nexus_keywords: false,
+            ..ScanConfig::default()
+        };
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(nexus.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), None);
+    }
+
+    #[test]
+    fn clean_counts_artifacts_and_metadata_separately() {
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        let remote = repo.write(
+            "com/example/foo/1.0/_remote.repositories",
+            b"foo-1.0.jar>central=\n",
+        );
+        let item = CleanItem {
+            folder: display_path(jar.parent().unwrap()),
+            base_name: "foo-1.0".to_string(),
+        };
+
+        let result = clean_artifacts(vec![item], repo.path_string(), None).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.artifacts_deleted, 1);
+        assert_eq!(result.metadata_deleted, 1);
+        assert!(!jar.exists());
+        assert!(!remote.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn validator_failure_is_reported() {
+        let repo = TempRepo::new();
+        let script = repo.write(
+            "validate.sh",
+            "case \"$1\" in\n  *bar-1.0.jar) echo \"signature invalid\"; exit 1 ;;\nesac\nexit 0\n",
+        );
+        let good = repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let bad = repo.write("com/example/bar/1.0/bar-1.0.jar", valid_jar());
+        let cmd = format!("sh {} {{path}}", script.display());
+        let timeout = Duration::from_secs(10);
+
+        assert!(run_validator(&cmd, &good, timeout).is_none());
+        let finding = run_validator(&cmd, &bad, timeout).unwrap();
+        assert_eq!(finding.reason, Reason::ValidatorFailed);
+        assert!(finding.message.contains("signature invalid"));
+
+        // 扫描时只有失败的构件被报告
+        let config = ScanConfig {
+            validator_cmd: Some(cmd),
+            ..ScanConfig::default()
+        };
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.artifacts.len(), 1);
+        assert_eq!(report.artifacts[0].base_name, "bar-1.0");
+        assert_eq!(report.artifacts[0].reason, Reason::ValidatorFailed);
+    }
+
+    #[test]
+    fn unused_artifacts_include_old_jars() {
+        let repo = TempRepo::new();
+        let old = repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        repo.write("com/example/bar/1.0/bar-1.0.jar", valid_jar());
+        set_file_times(
+            &old,
+            SystemTime::now() - Duration::from_secs(400 * 24 * 60 * 60),
+        );
+
+        let unused = scan_unused_artifacts(repo.path_string(), 365).unwrap();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].base_name, "foo-1.0");
+        assert!(unused[0].idle_days >= 400);
+    }
+
+    #[test]
+    fn commands_run_while_scan_in_progress() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        let state = Arc::new(AppState::default());
+        *state.repo_path_override.lock().unwrap() = Some(repo.path_string());
+
+        // 与 `scan_invalid_artifacts` 相同，扫描在异步运行时的阻塞线程中执行
+        let pause = begin_scan(&state).unwrap();
+        pause.set_paused(true);
+        let scan = tauri::async_runtime::spawn(run_scan_blocking(
+            repo.path_string(),
+            ScanConfig::default(),
+            Arc::clone(&pause),
+        ));
+
+        // 扫描被暂停在第一个文件前，此时运行时上的其他命令照常完成
+        let command = {
+            let state = Arc::clone(&state);
+            tauri::async_runtime::spawn(async move {
+                detect_repo_location(&state, None, &isolated_detect_env())
+            })
+        };
+        let location = tauri::async_runtime::block_on(command).unwrap().unwrap();
+        assert_eq!(location.source, RepoSource::Override);
+        assert!(state.last_scan.lock().unwrap().is_none());
+
+        pause.set_paused(false);
+        let report = tauri::async_runtime::block_on(scan).unwrap().unwrap();
+        assert_eq!(report.total_invalid, 1);
+        store_last_scan(&state, Some(report)).unwrap();
+        assert!(state.last_scan.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn manifest_naming_other_artifact_is_flagged() {
+        let jar_with_title = |title: &str| {
+            let manifest = format!(
+                "Manifest-Version: 1.0\r\nImplementation-Title: {}\r\n\r\n",
+                title
+            );
+            zip_bytes(&[
+                ("META-INF/MANIFEST.MF", manifest.as_bytes()),
+                ("com/example/Foo.class", &[0xca; 2048]),
+            ])
+        };
+        let mut archive = ZipArchive::new(Cursor::new(jar_with_title("commons-lang3"))).unwrap();
+        assert!(manifest_mismatches(&mut archive, "guava"));
+        let mut archive =
+            ZipArchive::new(Cursor::new(jar_with_title("Guava: Google Core Libraries"))).unwrap();
+        assert!(!manifest_mismatches(&mut archive, "guava"));
+
+        // 放进了 guava 目录的 commons-lang3
+        let repo = TempRepo::new();
+        let jar = repo.write(
+            "com/google/guava/guava/33.0/guava-33.0.jar",
+            jar_with_title("commons-lang3"),
+        );
+        let config = ScanConfig {
+            check_manifest: true,
+            ..ScanConfig::default()
+        };
+        let finding = classify_artifact(&jar, ArtifactInput::File(&jar), &config);
+        assert_eq!(reason_of(finding), Some(Reason::ManifestMismatch));
+    }
+
+    #[test]
+    fn reason_serializes_to_stable_code() {
+        assert_eq!(
+            serde_json::to_string(&Reason::TooSmall).unwrap(),
+            "\"too_small\""
+        );
+        assert_eq!(
+            serde_json::to_string(&Reason::HarborPom).unwrap(),
+            "\"harbor_pom\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Reason>("\"corrupt_zip\"").unwrap(),
+            Reason::CorruptZip
+        );
+
+        let artifact = InvalidArtifact::new(
+            "/repo/com/example/foo/1.0".to_string(),
+            "foo-1.0".to_string(),
+            Finding::new(Reason::CorruptZip),
+        );
+        let json = serde_json::to_value(&artifact).unwrap();
+        assert_eq!(json["reason"], "corrupt_zip");
+        assert_eq!(json["message"], Reason::CorruptZip.message());
+    }
+
+    #[test]
+    fn semaphore_bounds_concurrent_holders() {
+        let semaphore = Semaphore::new(2);
+        let holders = AtomicUsize::new(0);
+        let max_holders = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let _permit = semaphore.acquire();
+                    let current = holders.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_holders.fetch_max(current, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    holders.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        assert!(max_holders.load(Ordering::SeqCst) <= 2);
+        // 许可全部归还后可以再次获取
+        let _first = semaphore.acquire();
+        let _second = semaphore.acquire();
+    }
+
+    #[test]
+    fn partial_download_is_reported_and_removed() {
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let part = repo.write("com/example/foo/1.0/foo-1.0.jar.part", b"partial");
+
+        let report = run_scan(repo.path(), ScanConfig::default(), &ScanPause::default()).unwrap();
+        assert_eq!(report.artifacts.len(), 1);
+        let invalid = &report.artifacts[0];
+        assert_eq!(invalid.reason, Reason::PartialDownload);
+        assert_eq!(invalid.base_name, "foo-1.0.jar.part");
+
+        let item = CleanItem {
+            folder: invalid.folder.clone(),
+            base_name: invalid.base_name.clone(),
+        };
+        let result = clean_artifacts(vec![item], repo.path_string(), None).unwrap();
+        assert_eq!(result.artifacts_deleted, 1);
+        assert!(!part.exists());
+        assert!(jar.exists());
+    }
+
+    #[test]
+    fn set_local_repository_updates_or_inserts() {
+        let updated = set_local_repository(
+            "<settings>\n  <localRepository>/old</localRepository>\n  <offline>false</offline>\n</settings>\n",
+            "/new",
+        )
+        .unwrap();
+        assert_eq!(
+            updated,
+            "<settings>\n  <localRepository>/new</localRepository>\n  <offline>false</offline>\n</settings>\n"
+        );
+
+        let updated = set_local_repository(
+            "<settings>\n    <!-- keep -->\n    <offline>false</offline>\n</settings>\n",
+            "/new",
+        )
+        .unwrap();
+        assert_eq!(
+            updated,
+            "<settings>\n    <!-- keep -->\n    <localRepository>/new</localRepository>\n    <offline>false</offline>\n</settings>\n"
+        );
+
+        let updated = set_local_repository("<settings/>", "/new").unwrap();
+        assert_eq!(
+            updated,
+            "<settings>\n  <localRepository>/new</localRepository>\n</settings>"
+        );
+
+        assert!(set_local_repository("<project/>", "/new").is_err());
+    }
+
+    #[test]
+    fn write_local_repository_creates_settings_from_skeleton() {
+        let repo = TempRepo::new();
+        let settings = repo.path().join("conf").join("settings.xml");
+        write_local_repository(
+            settings.to_string_lossy().into_owned(),
+            "/data/a&b/repository".to_string(),
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&settings).unwrap();
+        assert!(content.contains("<localRepository>/data/a&amp;b/repository</localRepository>"));
+        let doc = roxmltree::Document::parse(&content).unwrap();
+        let value = doc
+            .descendants()
+            .find(|n| n.has_tag_name("localRepository"))
+            .and_then(|n| n.text());
+        assert_eq!(value, Some("/data/a&b/repository"));
+    }
+
+    #[test]
+    fn clean_keeps_metadata_when_asked() {
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        let remote = repo.write(
+            "com/example/foo/1.0/_remote.repositories",
+            b"foo-1.0.jar>central=\n",
+        );
+        let item = CleanItem {
+            folder: display_path(jar.parent().unwrap()),
+            base_name: "foo-1.0".to_string(),
+        };
+        let options = CleanOptions {
+            delete_metadata: false,
+            // 需要保留元数据时不能整目录删除
+            remove_whole_version_dir: true,
+            ..CleanOptions::default()
+        };
+
+        let result = clean_artifacts(vec![item], repo.path_string(), Some(options)).unwrap();
+        assert_eq!(result.artifacts_deleted, 1);
+        assert_eq!(result.metadata_deleted, 0);
+        assert!(!jar.exists());
+        assert!(remote.exists());
+    }
+
+    #[test]
+    fn deep_verify_detects_corrupted_entry() {
+        let payload = b"class file payload";
+        let mut jar = zip_bytes(&[
+            ("com/example/Foo.class", &[0xca; 2048]),
+            ("com/example/Bar.class", payload),
+        ]);
+        // 条目未压缩，直接改写其中一个字节，中央目录保持完好
+        let offset = jar
+            .windows(payload.len())
+            .position(|w| w == payload)
+            .unwrap();
+        jar[offset] ^= 0xff;
+
+        let classify = |deep_verify| {
+            let config = ScanConfig {
+                deep_verify,
+                ..ScanConfig::default()
+            };
+            reason_of(classify_artifact(
+                Path::new("foo-1.0.jar"),
+                ArtifactInput::Bytes(&jar),
+                &config,
+            ))
+        };
+        assert_eq!(classify(false), None);
+        assert_eq!(classify(true), Some(Reason::EntryCrcMismatch));
+
+        let config = ScanConfig {
+            deep_verify: true,
+            ..ScanConfig::default()
+        };
+        let valid = valid_jar();
+        assert_eq!(
+            reason_of(classify_artifact(
+                Path::new("foo-1.0.jar"),
+                ArtifactInput::Bytes(&valid),
+                &config
+            )),
+            None
+        );
+    }
+
+    /// Maven 写入的 `_remote.repositories` 样例
+    const REMOTE_REPOSITORIES: &str = "#NOTE: This is a Maven Resolver internal implementation file, its format can be changed without prior notice.\n#Mon Jan 01 12:00:00 CST 2024\nfoo-1.0.jar>central=\nfoo-1.0.pom>central=\nfoo-1.0-sources.jar>nexus=\nfoo-1.0-tests.jar>=\n";
+
+    #[test]
+    fn parse_remote_repositories_sample() {
+        assert_eq!(
+            parse_remote_repositories(REMOTE_REPOSITORIES),
+            vec![
+                ("foo-1.0.jar".to_string(), "central".to_string()),
+                ("foo-1.0.pom".to_string(), "central".to_string()),
+                ("foo-1.0-sources.jar".to_string(), "nexus".to_string()),
+                ("foo-1.0-tests.jar".to_string(), "(local)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn remote_sources_match_invalid_artifacts_exactly() {
+        let repo = TempRepo::new();
+        let remote = repo.write(
+            "com/example/foo/1.0/_remote.repositories",
+            REMOTE_REPOSITORIES,
+        );
+        let invalid = CleanItem {
+            folder: display_path(&fs::canonicalize(remote.parent().unwrap()).unwrap()),
+            base_name: "foo-1.0".to_string(),
+        };
+
+        let stats = scan_remote_sources(repo.path_string(), Some(vec![invalid])).unwrap();
+        let stat = |id: &str| {
+            let stat = stats.iter().find(|s| s.remote_id == id).unwrap();
+            (stat.artifact_count, stat.invalid_count)
+        };
+        assert_eq!(stat("central"), (2, 2));
+        // foo-1.0-sources.jar 以 foo-1.0 开头，但属于另一个构件
+        assert_eq!(stat("nexus"), (1, 0));
+        assert_eq!(stat("(local)"), (1, 0));
+    }
+
+    #[test]
+    fn scan_uses_configured_thread_count() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let config = ScanConfig {
+            thread_count: Some(2),
+            ..ScanConfig::default()
+        };
+
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.thread_count, 2);
+    }
+
+    #[test]
+    fn conflicting_sha1_files_are_flagged() {
+        let repo = TempRepo::new();
+        let sha1 = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        let files = vec![
+            repo.write("com/example/foo/1.0/foo-1.0.jar.sha1", sha1),
+            repo.write(
+                "com/example/foo/1.0/foo-1.0.jar.sha1.sha1",
+                "0000000000000000000000000000000000000000",
+            ),
+            // 内容相同（仅大小写和文件名不同）不算冲突
+            repo.write("com/example/bar/1.0/bar-1.0.jar.sha1", sha1),
+            repo.write(
+                "com/example/bar/1.0/bar-1.0.jar.SHA1",
+                format!("{}  bar-1.0.jar\n", sha1.to_uppercase()),
+            ),
+        ];
+
+        let conflicts = find_checksum_conflicts(&files, &Semaphore::new(4));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].base_name, "foo-1.0");
+        assert_eq!(conflicts[0].reason, Reason::ConflictingChecksums);
+    }
+
+    #[test]
+    fn fail_fast_stops_at_first_error() {
+        let run = |fail_fast| {
+            let repo = TempRepo::new();
+            // 与构件同名的非空目录无法按文件删除，即使以 root 运行也会失败
+            let stuck = repo.write("com/example/foo/1.0/foo-1.0.jar/entry", b"");
+            let jar = repo.write("com/example/bar/1.0/bar-1.0.jar", b"bad");
+            let items = vec![
+                CleanItem {
+                    folder: display_path(stuck.parent().unwrap().parent().unwrap()),
+                    base_name: "foo-1.0".to_string(),
+                },
+                CleanItem {
+                    folder: display_path(jar.parent().unwrap()),
+                    base_name: "bar-1.0".to_string(),
+                },
+            ];
+            let options = CleanOptions {
+                fail_fast,
+                delete_attempts: 1,
+                ..CleanOptions::default()
+            };
+            let result = clean_artifacts(items, repo.path_string(), Some(options)).unwrap();
+            (result.errors.len(), result.artifacts_deleted, jar.exists())
+        };
+
+        assert_eq!(run(true), (1, 0, true));
+        assert_eq!(run(false), (1, 1, false));
+    }
+
+    #[test]
+    fn classify_flags_artifactory_errors() {
+        let config = ScanConfig::default();
+        let html = "<!DOCTYPE html>\n<html><head><title>JFrog Artifactory</title></head>\n<body><h1>404 Not Found</h1><address>Artifactory/7.55.10 Server</address></body></html>\n";
+        let json = "{\n  \"errors\" : [ {\n    \"status\" : 404,\n    \"message\" : \"Could not find resource\"\n  } ]\n}";
+
+        for body in [html, json] {
+            for file_name in ["foo-1.0.pom", "foo-1.0.jar"] {
+                let finding = classify_artifact(
+                    Path::new(file_name),
+                    ArtifactInput::Bytes(body.as_bytes()),
+                    &config,
+                );
+                assert_eq!(
+                    reason_of(finding),
+                    Some(Reason::ArtifactoryError),
+                    "{}",
+                    file_name
+                );
+            }
+        }
+
+        // 关闭后 HTML 页面退回通用的错误页面检测，JSON 不再命中
+        let config = ScanConfig {
+            artifactory_keywords: false,
+            ..ScanConfig::default()
+        };
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(html.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::HarborPom));
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(json.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), None);
+    }
+
+    #[test]
+    fn equivalent_repo_paths_report_same_folders() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        fs::create_dir_all(repo.path().join("sub")).unwrap();
+
+        let folders = |repo_path: PathBuf| {
+            let report =
+                run_scan(&repo_path, ScanConfig::default(), &ScanPause::default()).unwrap();
+            report
+                .artifacts
+                .into_iter()
+                .map(|a| a.folder)
+                .collect::<Vec<_>>()
+        };
+        let expected = folders(repo.path().to_path_buf());
+        assert_eq!(expected.len(), 1);
+        assert_eq!(
+            folders(repo.path().join(".").join("sub").join("..")),
+            expected
+        );
+        assert_eq!(
+            folders(PathBuf::from(format!("{}/", repo.path_string()))),
+            expected
+        );
+    }
+
+    #[test]
+    fn size_by_group_sums_per_prefix() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", [0u8; 100]);
+        repo.write("com/example/foo/1.0/foo-1.0.pom", [0u8; 50]);
+        repo.write("org/acme/bar/1.0/bar-1.0.jar", [0u8; 300]);
+
+        let summary = |depth| {
+            size_by_group(repo.path_string(), depth)
+                .unwrap()
+                .into_iter()
+                .map(|g| (g.group, g.size, g.file_count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(None),
+            vec![("org".to_string(), 300, 1), ("com".to_string(), 150, 2)]
+        );
+        assert_eq!(
+            summary(Some(2)),
+            vec![
+                ("org.acme".to_string(), 300, 1),
+                ("com.example".to_string(), 150, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn file_as_repo_is_not_a_directory() {
+        let repo = TempRepo::new();
+        let file = repo.write("not-a-repo.txt", b"");
+
+        let err = ensure_repo_dir(&file).unwrap_err();
+        assert!(err.starts_with(NOT_A_DIRECTORY_PREFIX), "{}", err);
+        let err = run_scan(&file, ScanConfig::default(), &ScanPause::default()).unwrap_err();
+        assert!(err.starts_with(NOT_A_DIRECTORY_PREFIX), "{}", err);
+        let err = size_by_group(file.to_string_lossy().into_owned(), None).unwrap_err();
+        assert!(err.starts_with(NOT_A_DIRECTORY_PREFIX), "{}", err);
+        let err = ensure_repo_dir(&repo.path().join("missing")).unwrap_err();
+        assert!(err.starts_with("仓库路径不存在"), "{}", err);
+
+        // settings.xml 中指向文件或不存在路径的 localRepository 被忽略
+        for target in [file.clone(), repo.path().join("missing")] {
+            let settings = repo.write(
+                "settings.xml",
+                format!(
+                    "<settings><localRepository>{}</localRepository></settings>",
+                    target.display()
+                ),
+            );
+            let (parsed, log) = with_log_capture(|| parse_local_repo(&settings));
+            assert_eq!(parsed, None);
+            assert!(log.iter().any(|line| line.contains("不是已存在的目录")
+                || line.contains("not an existing directory")));
+        }
+
+        let settings = repo.write(
+            "settings.xml",
+            format!(
+                "<settings><localRepository>{}</localRepository></settings>",
+                repo.path().display()
+            ),
+        );
+        assert_eq!(parse_local_repo(&settings), Some(repo.path_string()));
+    }
+
+    #[test]
+    fn quick_scan_checks_size_only() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", [0u8; 50]);
+        repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+        repo.write("com/example/bar/1.0/bar-1.0.jar", valid_jar());
+        let config = ScanConfig {
+            quick: true,
+            ..ScanConfig::default()
+        };
+
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.artifacts.len(), 1);
+        assert_eq!(report.artifacts[0].base_name, "foo-1.0");
+        assert_eq!(report.artifacts[0].reason, Reason::TooSmall);
+    }
+
+    #[test]
+    fn case_collisions_are_grouped() {
+        let repo = TempRepo::new();
+        let upper = repo.write("com/example/foo/1.0/Foo-1.0.jar", b"upper");
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"lower");
+        repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+        // 不区分大小写的文件系统上两个文件是同一个，无法构造冲突
+        if fs::read_dir(upper.parent().unwrap()).unwrap().count() != 3 {
+            return;
+        }
+
+        let collisions = scan_case_collisions(repo.path_string()).unwrap();
+        assert_eq!(collisions.len(), 1);
+        let names: Vec<_> = collisions[0]
+            .paths
+            .iter()
+            .map(|p| {
+                Path::new(p)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        assert_eq!(names, ["Foo-1.0.jar", "foo-1.0.jar"]);
+    }
+
+    #[test]
+    fn scan_completion_emits_one_summary() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+        repo.write("com/example/bar/1.0/bar-1.0.jar", valid_jar());
+        let state = AppState::default();
+
+        let pause = begin_scan(&state).unwrap();
+        let report = run_scan(repo.path(), ScanConfig::default(), &pause).unwrap();
+        let mut events = Vec::new();
+        complete_scan(&state, &report, |event, summary| {
+            events.push((event.to_string(), summary));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(events.len(), 1);
+        let (event, summary) = &events[0];
+        assert_eq!(event, SCAN_COMPLETE_EVENT);
+        assert_eq!(summary.total_files, 3);
+        assert_eq!(summary.invalid_count, 2);
+        assert_eq!(summary.reason_counts.get(&Reason::CorruptZip), Some(&1));
+        assert_eq!(summary.reason_counts.get(&Reason::HarborPom), Some(&1));
+        assert!(state.last_scan.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn manifest_only_jar_is_empty() {
+        let manifest: &[u8] = &[b'#'; 2048];
+        let jar = zip_bytes(&[("META-INF/MANIFEST.MF", manifest)]);
+        let classify = |check_empty_jar| {
+            let config = ScanConfig {
+                check_empty_jar,
+                ..ScanConfig::default()
+            };
+            reason_of(classify_artifact(
+                Path::new("foo-1.0.jar"),
+                ArtifactInput::Bytes(&jar),
+                &config,
+            ))
+        };
+        assert_eq!(classify(false), None);
+        assert_eq!(classify(true), Some(Reason::EmptyJar));
+
+        let config = ScanConfig {
+            check_empty_jar: true,
+            ..ScanConfig::default()
+        };
+        let valid = valid_jar();
+        assert_eq!(
+            reason_of(classify_artifact(
+                Path::new("foo-1.0.jar"),
+                ArtifactInput::Bytes(&valid),
+                &config
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn custom_settings_repo_wins() {
+        let repo = TempRepo::new();
+        let settings = repo.write(
+            "custom-settings.xml",
+            format!(
+                "<settings>\n  <localRepository>{}</localRepository>\n</settings>\n",
+                repo.path().display()
+            ),
+        );
+        let state = AppState::default();
+
+        let location = detect_repo_location(
+            &state,
+            Some(settings.to_string_lossy().into_owned()),
+            &isolated_detect_env(),
+        )
+        .unwrap();
+        assert_eq!(location.source, RepoSource::CustomSettings);
+        assert_eq!(location.path, repo.path_string());
+        assert!(!location.maven_found);
+    }
+
+    #[test]
+    fn check_single_artifact_reports_bad_file_only() {
+        let repo = TempRepo::new();
+        let bad = repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+        let good = repo.write("com/example/bar/1.0/bar-1.0.jar", valid_jar());
+        let check = |path: &Path| {
+            check_single_artifact(
+                path.to_string_lossy().into_owned(),
+                Some(repo.path_string()),
+                None,
+            )
+            .unwrap()
+        };
+
+        let invalid = check(&bad).unwrap();
+        assert_eq!(invalid.reason, Reason::HarborPom);
+        assert_eq!(invalid.base_name, "foo-1.0");
+        assert!(check(&good).is_none());
+        assert!(check_single_artifact(repo.path_string(), None, None).is_err());
+    }
+
+    #[test]
+    fn bom_prefixed_harbor_pom_is_flagged() {
+        let config = ScanConfig::default();
+        let pom = format!("\u{feff}\n  {}", HARBOR_POM);
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(pom.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::HarborPom));
+        assert_eq!(strip_leading_noise(&pom), HARBOR_POM);
+    }
+
+    #[test]
+    fn last_scan_cached_until_next_scan() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.jar", b"bad");
+        let state = AppState::default();
+        assert!(load_last_scan(&state).unwrap().is_none());
+
+        let pause = begin_scan(&state).unwrap();
+        let report = run_scan(repo.path(), ScanConfig::default(), &pause).unwrap();
+        complete_scan(&state, &report, |_, _| Ok(())).unwrap();
+        let cached = load_last_scan(&state).unwrap().unwrap();
+        assert_eq!(cached.total_invalid, 1);
+        assert_eq!(cached.artifacts[0].base_name, "foo-1.0");
+
+        // 新的扫描开始后旧结果立即失效，失败的扫描不会留下结果
+        begin_scan(&state).unwrap();
+        assert!(load_last_scan(&state).unwrap().is_none());
+        assert!(run_scan(&repo.path().join("missing"), ScanConfig::default(), &pause).is_err());
+        assert!(load_last_scan(&state).unwrap().is_none());
+    }
+
+    #[test]
+    fn http_headers_before_artifact_are_flagged() {
+        let config = ScanConfig::default();
+        let mut jar = b"HTTP/1.1 200 OK\r\nContent-Type: application/java-archive\r\n\r\n".to_vec();
+        jar.extend(valid_jar());
+        let finding = classify_artifact(
+            Path::new("foo-1.0.jar"),
+            ArtifactInput::Bytes(&jar),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::HttpHeaders));
+
+        let pom = format!("Content-Length: {}\r\n\r\n{}", VALID_POM.len(), VALID_POM);
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(pom.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::HttpHeaders));
+
+        let config = ScanConfig {
+            check_http_headers: false,
+            ..ScanConfig::default()
+        };
+        let finding = classify_artifact(
+            Path::new("foo-1.0.pom"),
+            ArtifactInput::Bytes(pom.as_bytes()),
+            &config,
+        );
+        assert_eq!(reason_of(finding), None);
+    }
+
+    #[test]
+    fn purge_checksums_removes_only_checksums() {
+        let repo = TempRepo::new();
+        let dir = "com/example/foo/1.0";
+        let jar = repo.write(&format!("{}/foo-1.0.jar", dir), valid_jar());
+        let remote = repo.write(&format!("{}/_remote.repositories", dir), b"");
+        let sources_sha1 = repo.write(&format!("{}/foo-1.0-sources.jar.sha1", dir), b"");
+        let purged: Vec<PathBuf> = ["foo-1.0.jar.sha1", "foo-1.0.jar.md5", "foo-1.0.pom.sha1"]
+            .iter()
+            .map(|name| repo.write(&format!("{}/{}", dir, name), b"0"))
+            .collect();
+        let item = CleanItem {
+            folder: display_path(jar.parent().unwrap()),
+            base_name: "foo-1.0".to_string(),
+        };
+
+        let result = purge_checksums(vec![item], repo.path_string()).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.checksums_deleted, 3);
+        assert!(purged.iter().all(|p| !p.exists()));
+        assert!(jar.exists());
+        assert!(remote.exists());
+        assert!(sources_sha1.exists());
+    }
+
+    /// 与进程环境隔离的检测环境：PATH 为空（找不到 mvn），未设置任何 Maven 变量
+    fn isolated_detect_env() -> DetectEnv {
+        DetectEnv {
+            maven_home: None,
+            m2_home: None,
+            path: Some(std::ffi::OsString::new()),
+            home_dir: None,
+        }
+    }
+
+    /// 依次走过每个检测分支；环境通过 `DetectEnv` 传入，不修改进程环境变量
+    #[cfg(unix)]
+    #[test]
+    fn repo_source_per_detection_branch() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sandbox = TempRepo::new();
+        let settings = |repo: &Path| {
+            format!(
+                "<settings><localRepository>{}</localRepository></settings>",
+                repo.display()
+            )
+        };
+
+        let maven_home = sandbox.path().join("maven-home");
+        let global_repo = sandbox.path().join("global-repo");
+        fs::create_dir_all(&global_repo).unwrap();
+        sandbox.write("maven-home/conf/settings.xml", settings(&global_repo));
+        let mvn = sandbox.write(
+            "fake-bin/mvn",
+            format!(
+                "#!/bin/sh\necho 'Apache Maven 3.9.6'\necho 'Maven home: {}'\n",
+                maven_home.display()
+            ),
+        );
+        fs::set_permissions(&mvn, fs::Permissions::from_mode(0o755)).unwrap();
+        let home = sandbox.path().join("home");
+        fs::create_dir_all(&home).unwrap();
+
+        let state = AppState::default();
+        let detect = |env: &DetectEnv| {
+            let location = detect_repo_location(&state, None, env).unwrap();
+            (
+                location.source,
+                location.maven_found,
+                PathBuf::from(location.path),
+            )
+        };
+
+        let env = DetectEnv {
+            path: Some(mvn.parent().unwrap().as_os_str().to_owned()),
+            home_dir: Some(home.clone()),
+            ..isolated_detect_env()
+        };
+        assert_eq!(
+            detect(&env),
+            (RepoSource::MvnCommand, true, global_repo.clone())
+        );
+
+        let env = DetectEnv {
+            maven_home: Some(maven_home.to_string_lossy().into_owned()),
+            home_dir: Some(home.clone()),
+            ..isolated_detect_env()
+        };
+        assert_eq!(detect(&env), (RepoSource::EnvVar, true, global_repo));
+
+        let env = DetectEnv {
+            home_dir: Some(home.clone()),
+            ..isolated_detect_env()
+        };
+        let user_repo = sandbox.path().join("user-repo");
+        fs::create_dir_all(&user_repo).unwrap();
+        let user_settings = sandbox.write("home/.m2/settings.xml", settings(&user_repo));
+        assert_eq!(detect(&env), (RepoSource::UserSettings, false, user_repo));
+
+        fs::remove_file(&user_settings).unwrap();
+        assert_eq!(
+            detect(&env),
+            (
+                RepoSource::Default,
+                false,
+                home.join(".m2").join("repository")
+            )
+        );
+    }
+
+    #[test]
+    fn jar_packaging_pom_without_jar_is_flagged() {
+        let repo = TempRepo::new();
+        let config = ScanConfig {
+            check_missing_jar: true,
+            ..ScanConfig::default()
+        };
+        let classify =
+            |path: &Path| reason_of(classify_artifact(path, ArtifactInput::File(path), &config));
+
+        // packaging 为 pom 的 POM 本来就没有 JAR
+        let pom = repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+        assert_eq!(classify(&pom), None);
+
+        let pom = repo.write(
+            "com/example/bar/1.0/bar-1.0.pom",
+            VALID_POM.replace("<packaging>pom</packaging>", "<packaging>jar</packaging>"),
+        );
+        assert_eq!(classify(&pom), Some(Reason::MissingMainJar));
+
+        repo.write("com/example/bar/1.0/bar-1.0.jar", valid_jar());
+        assert_eq!(classify(&pom), None);
+    }
+
+    #[test]
+    fn debug_log_records_executed_checks() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+        repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let log_path = repo.dir.join("debug.jsonl");
+        let config = ScanConfig {
+            debug_log_path: Some(log_path.to_string_lossy().into_owned()),
+            ..ScanConfig::default()
+        };
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.total_invalid, 1);
+
+        let entries: HashMap<String, serde_json::Value> = fs::read_to_string(&log_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .map(|entry| {
+                let path = entry["path"].as_str().unwrap();
+                let file_name = Path::new(path).file_name().unwrap().to_string_lossy();
+                (file_name.into_owned(), entry)
+            })
+            .collect();
+
+        let pom = &entries["foo-1.0.pom"];
+        assert_eq!(pom["verdict"], serde_json::json!(Reason::HarborPom));
+        assert!(pom["checks"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("harbor_keywords")));
+
+        let jar = &entries["foo-1.0.jar"];
+        assert!(jar["verdict"].is_null());
+        assert!(jar["checks"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("zip")));
+    }
+
+    #[test]
+    fn recorded_size_mismatch_is_flagged() {
+        assert_eq!(
+            parse_recorded_sizes("#note\nfoo-1.0.jar>central=2048\nfoo-1.0.pom>central=\n"),
+            HashMap::from([("foo-1.0.jar".to_string(), 2048)])
+        );
+
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let size = fs::metadata(&jar).unwrap().len();
+        let config = ScanConfig {
+            check_recorded_size: true,
+            ..ScanConfig::default()
+        };
+        let classify = || reason_of(classify_artifact(&jar, ArtifactInput::File(&jar), &config));
+
+        repo.write(
+            "com/example/foo/1.0/_remote.repositories",
+            format!("foo-1.0.jar>central={}\n", size),
+        );
+        assert_eq!(classify(), None);
+
+        // 代理记录的大小比磁盘上的多：文件在仍能打开的位置被截断
+        repo.write(
+            "com/example/foo/1.0/_remote.repositories",
+            format!("foo-1.0.jar>central={}\n", size + 4096),
+        );
+        assert_eq!(classify(), Some(Reason::SizeMismatch));
+    }
+
+    #[test]
+    fn missing_parent_pom_is_flagged() {
+        let repo = TempRepo::new();
+        let child = repo.write(
+            "com/example/child/1.0/child-1.0.pom",
+            VALID_POM.replace(
+                "<groupId>com.example</groupId>",
+                "<parent>\n    <groupId>com.example</groupId>\n    <artifactId>parent</artifactId>\n    <version>2.0</version>\n  </parent>\n  <groupId>com.example</groupId>",
+            ),
+        );
+        let config = ScanConfig {
+            check_parent_pom: true,
+            ..ScanConfig::default()
+        };
+        let limits = ScanLimits::new(&config);
+        let check = || check_file(&child, Some(repo.path()), &config, &limits);
+
+        let invalid = check().unwrap();
+        assert_eq!(invalid.reason, Reason::MissingParentPom);
+        assert!(invalid.message.contains("com.example:parent:2.0"));
+
+        // 不知道仓库根目录时无法定位父 POM，跳过该检查
+        assert!(check_file(&child, None, &config, &limits).is_none());
+
+        repo.write("com/example/parent/2.0/parent-2.0.pom", VALID_POM);
+        assert!(check().is_none());
+    }
+
+    #[test]
+    fn small_jar_is_flagged_by_classifier_threshold() {
+        let repo = TempRepo::new();
+        let small = zip_bytes(&[("com/example/Foo.class", &[0xca; 300])]);
+        assert!((100..1024).contains(&small.len()));
+        let main = repo.write("com/example/foo/1.0/foo-1.0.jar", &small);
+        let sources = repo.write("com/example/foo/1.0/foo-1.0-sources.jar", &small);
+        let config = ScanConfig::default();
+
+        // 完整检查同样按 classifier 的大小下限检查：主 JAR 为 1KB，sources 不检查
+        let finding = classify_artifact(&main, ArtifactInput::File(&main), &config);
+        assert_eq!(reason_of(finding), Some(Reason::TooSmall));
+        let finding = classify_artifact(&sources, ArtifactInput::File(&sources), &config);
+        assert_eq!(reason_of(finding), None);
+    }
+
+    #[test]
+    fn watch_loop_reports_invalid_artifact() {
+        let repo = TempRepo::new();
+        let (events, receiver) = mpsc::channel();
+        let (found, invalid) = mpsc::channel();
+        let root = repo.path().to_path_buf();
+        let watch = std::thread::spawn(move || {
+            run_watch_loop(receiver, root, ScanConfig::default(), |artifact| {
+                found.send(artifact).unwrap();
+            })
+        });
+
+        let pom = repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+        let event = notify::Event::new(notify::EventKind::Create(notify::event::CreateKind::File))
+            .add_path(pom.clone());
+        events.send(Ok(event)).unwrap();
+
+        // 写入稳定（超过 WATCH_DEBOUNCE）后才会检查
+        let artifact = invalid
+            .recv_timeout(WATCH_DEBOUNCE + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(artifact.reason, Reason::HarborPom);
+        assert_eq!(artifact.folder, display_path(pom.parent().unwrap()));
+
+        // 监视器丢弃后事件通道断开，循环退出
+        drop(events);
+        watch.join().unwrap();
+    }
+
+    #[test]
+    fn gzip_data_saved_as_jar_is_flagged() {
+        let mut jar = GZIP_MAGIC.to_vec();
+        jar.extend([0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03]);
+        jar.extend([0u8; 64]);
+        let config = ScanConfig::default();
+        let finding = classify_artifact(
+            Path::new("foo-1.0.jar"),
+            ArtifactInput::Bytes(&jar),
+            &config,
+        );
+        assert_eq!(reason_of(finding), Some(Reason::GzipJar));
+
+        let repo = TempRepo::new();
+        let path = repo.write("com/example/foo/1.0/foo-1.0.jar", &jar);
+        let finding = classify_artifact(&path, ArtifactInput::File(&path), &config);
+        assert_eq!(reason_of(finding), Some(Reason::GzipJar));
+    }
+
+    #[test]
+    fn results_are_capped_at_max_results() {
+        let repo = TempRepo::new();
+        for i in 0..5 {
+            repo.write(&format!("com/example/a{i}/1.0/a{i}-1.0.jar"), b"not a zip");
+        }
+        let config = ScanConfig {
+            max_results: Some(2),
+            ..ScanConfig::default()
+        };
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert!(report.truncated);
+        assert_eq!(report.total_invalid, 5);
+        assert_eq!(report.artifacts.len(), 2);
+        // 按目录排序后保留前面的条目
+        assert_eq!(report.artifacts[0].base_name, "a0-1.0");
+        assert_eq!(report.artifacts[1].base_name, "a1-1.0");
+    }
+
+    #[test]
+    fn gradle_scan_uses_configured_thread_count() {
+        let repo = TempRepo::new();
+        repo.write(
+            "files-2.1/com.example/foo/1.0/0123abcd/foo-1.0.pom",
+            HARBOR_POM,
+        );
+        repo.write(
+            "files-2.1/com.example/foo/1.0/4567cdef/foo-1.0.jar",
+            valid_jar(),
+        );
+        let config = ScanConfig {
+            thread_count: Some(3),
+            ..ScanConfig::default()
+        };
+        let report = run_gradle_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.thread_count, 3);
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.total_invalid, 1);
+        assert_eq!(report.artifacts[0].reason, Reason::HarborPom);
+    }
+
+    #[test]
+    fn empty_checksum_is_flagged_or_regenerated() {
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", valid_jar());
+        let sha1 = repo.write("com/example/foo/1.0/foo-1.0.jar.sha1", b"");
+        let flag_only = ScanConfig {
+            check_empty_checksums: true,
+            ..ScanConfig::default()
+        };
+        let limits = ScanLimits::new(&flag_only);
+
+        let invalid = check_file(&sha1, None, &flag_only, &limits).unwrap();
+        assert_eq!(invalid.reason, Reason::EmptyChecksum);
+        assert_eq!(invalid.base_name, "foo-1.0.jar.sha1");
+        assert!(fs::read(&sha1).unwrap().is_empty());
+
+        let regenerate = ScanConfig {
+            regenerate_empty_checksums: true,
+            ..flag_only
+        };
+        assert!(check_file(&sha1, None, &regenerate, &limits).is_none());
+        assert_eq!(
+            fs::read_to_string(&sha1).unwrap(),
+            compute_sha1(&jar).unwrap()
+        );
+
+        // 对应的构件不存在时无法重新生成，仍然报告
+        let orphan = repo.write("com/example/foo/1.0/foo-1.0.pom.md5", b"");
+        let invalid = check_file(&orphan, None, &regenerate, &limits).unwrap();
+        assert_eq!(invalid.reason, Reason::EmptyChecksum);
+    }
+
+    #[test]
+    fn diff_reports_splits_added_and_removed() {
+        let artifact = |base_name: &str, reason| {
+            InvalidArtifact::new(
+                "/repo/com/example".to_string(),
+                base_name.to_string(),
+                Finding::new(reason),
+            )
+        };
+        let report = |artifacts: Vec<InvalidArtifact>| ScanReport {
+            reason_counts: count_reasons(&artifacts),
+            total_invalid: artifacts.len(),
+            artifacts,
+            files_scanned: 0,
+            duration_ms: 0,
+            collect_ms: 0,
+            check_ms: 0,
+            thread_count: 1,
+            truncated: false,
+        };
+        let before = report(vec![
+            artifact("kept-1.0", Reason::CorruptZip),
+            artifact("fixed-1.0", Reason::HarborPom),
+            artifact("changed-1.0", Reason::CorruptZip),
+        ]);
+        let after = report(vec![
+            artifact("kept-1.0", Reason::CorruptZip),
+            artifact("changed-1.0", Reason::TooSmall),
+            artifact("new-1.0", Reason::GzipJar),
+        ]);
+
+        let diff = diff_reports(before, after);
+        let keys = |artifacts: &[InvalidArtifact]| {
+            artifacts
+                .iter()
+                .map(|a| (a.base_name.clone(), a.reason))
+                .collect::<Vec<_>>()
+        };
+        // 同一构件原因变化时视为一条已修复、一条新增
+        assert_eq!(
+            keys(&diff.added),
+            vec![
+                ("changed-1.0".to_string(), Reason::TooSmall),
+                ("new-1.0".to_string(), Reason::GzipJar),
+            ]
+        );
+        assert_eq!(
+            keys(&diff.removed),
+            vec![
+                ("fixed-1.0".to_string(), Reason::HarborPom),
+                ("changed-1.0".to_string(), Reason::CorruptZip),
+            ]
+        );
+    }
+
+    #[test]
+    fn small_war_is_flagged_when_configured() {
+        let repo = TempRepo::new();
+        repo.write(
+            "com/example/web/1.0/web-1.0.war",
+            zip_bytes(&[("WEB-INF/web.xml", &[b' '; 300])]),
+        );
+
+        let report = run_scan(repo.path(), ScanConfig::default(), &ScanPause::default()).unwrap();
+        assert_eq!(report.files_scanned, 0);
+
+        let config = ScanConfig {
+            artifact_extensions: vec!["jar".to_string(), "pom".to_string(), "war".to_string()],
+            ..ScanConfig::default()
+        };
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.files_scanned, 1);
+        assert_eq!(report.artifacts.len(), 1);
+        assert_eq!(report.artifacts[0].base_name, "web-1.0");
+        assert_eq!(report.artifacts[0].reason, Reason::TooSmall);
+    }
+
+    #[test]
+    fn paused_scan_waits_until_resumed() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-1.0.pom", HARBOR_POM);
+        let pause = Arc::new(ScanPause::default());
+        pause.set_paused(true);
+
+        let scan = {
+            let pause = Arc::clone(&pause);
+            let root = repo.path().to_path_buf();
+            std::thread::spawn(move || run_scan(&root, ScanConfig::default(), &pause))
+        };
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(!scan.is_finished());
+
+        pause.set_paused(false);
+        let report = scan.join().unwrap().unwrap();
+        assert_eq!(report.total_invalid, 1);
+    }
+
+    #[test]
+    fn group_age_report_tracks_oldest_and_newest() {
+        let repo = TempRepo::new();
+        let day = |n: u64| UNIX_EPOCH + Duration::from_secs(1_700_000_000 + n * 24 * 60 * 60);
+        let files = [
+            ("com/example/foo/1.0/foo-1.0.jar", 3),
+            ("com/example/foo/2.0/foo-2.0.pom", 5),
+            ("com/example/bar/1.0/bar-1.0.jar", 1),
+            ("org/acme/baz/1.0/baz-1.0.jar", 2),
+        ];
+        for (path, age) in files {
+            let path = repo.write(path, valid_jar());
+            set_file_times(&path, day(age));
+        }
+
+        let groups = group_age_report(repo.path_string()).unwrap();
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.group.as_str(),
+                    g.oldest_mtime,
+                    g.newest_mtime,
+                    g.artifact_count,
+                )
+            })
+            .collect();
+        let secs = |n| day(n).duration_since(UNIX_EPOCH).unwrap().as_secs();
+        // 按最新修改时间升序
+        assert_eq!(
+            summary,
+            vec![
+                ("org.acme", secs(2), secs(2), 1),
+                ("com.example", secs(1), secs(5), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn artifact_base_name_strips_only_last_extension() {
+        assert_eq!(artifact_base_name("foo-1.0.jar"), "foo-1.0");
+        assert_eq!(artifact_base_name("a.pom.jar"), "a.pom");
+        assert_eq!(artifact_base_name("foo-1.0.jar.lock"), "foo-1.0.jar.lock");
+    }
+
+    #[test]
+    fn cleaning_artifact_keeps_classified_siblings() {
+        let repo = TempRepo::new();
+        let jar = repo.write("com/example/foo/1.0/foo-1.0.jar", b"not a zip");
+        let pom = repo.write("com/example/foo/1.0/foo-1.0.pom", VALID_POM);
+        let sources = repo.write("com/example/foo/1.0/foo-1.0-sources.jar", valid_jar());
+        let item = CleanItem {
+            folder: display_path(jar.parent().unwrap()),
+            base_name: "foo-1.0".to_string(),
+        };
+
+        let result = clean_artifacts(vec![item], repo.path_string(), None).unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.artifacts_deleted, 2);
+        assert!(!jar.exists());
+        assert!(!pom.exists());
+        assert!(sources.exists());
+    }
+
+    #[test]
+    fn size_exempt_sha1_skips_known_small_jar() {
+        let repo = TempRepo::new();
+        let small = zip_bytes(&[("com/example/Foo.class", &[0xca; 300])]);
+        let allowed = repo.write("com/example/foo/1.0/foo-1.0.jar", &small);
+        let other = repo.write(
+            "com/example/bar/1.0/bar-1.0.jar",
+            zip_bytes(&[("com/example/Bar.class", &[0xcb; 300])]),
+        );
+        let digest = compute_sha1(&allowed).unwrap().to_uppercase();
+
+        for quick in [false, true] {
+            let config = ScanConfig {
+                quick,
+                size_exempt_sha1: vec![format!(" {} ", digest)],
+                ..ScanConfig::default()
+            };
+            let limits = ScanLimits::new(&config);
+            assert!(check_file(&allowed, None, &config, &limits).is_none());
+            let invalid = check_file(&other, None, &config, &limits).unwrap();
+            assert_eq!(invalid.reason, Reason::TooSmall);
+        }
+    }
+
+    #[test]
+    fn scan_archive_applies_size_thresholds_and_quick_mode() {
+        let small = zip_bytes(&[("com/example/Foo.class", &[0xca; 300])]);
+        let archive = zip_bytes(&[
+            ("repository/com/example/foo/1.0/foo-1.0.jar", &small),
+            ("repository/com/example/foo/1.0/foo-1.0-sources.jar", &small),
+            (
+                "repository/com/example/bar/1.0/bar-1.0.pom",
+                HARBOR_POM.as_bytes(),
+            ),
+        ]);
+        let repo = TempRepo::new();
+        let archive_path = repo.write("backup.zip", archive);
+
+        let scan = |quick| {
+            let config = ScanConfig {
+                quick,
+                ..ScanConfig::default()
+            };
+            let report =
+                scan_archive(archive_path.to_string_lossy().into_owned(), Some(config)).unwrap();
+            report
+                .artifacts
+                .iter()
+                .map(|a| (a.base_name.clone(), a.reason))
+                .collect::<Vec<_>>()
+        };
+
+        // 归档条目同样按仓库布局解析 classifier：主 JAR 过小，sources 不检查
+        assert_eq!(
+            scan(false),
+            vec![
+                ("bar-1.0".to_string(), Reason::HarborPom),
+                ("foo-1.0".to_string(), Reason::TooSmall),
+            ]
+        );
+        // 快速模式不检查 POM 内容
+        assert_eq!(scan(true), vec![("foo-1.0".to_string(), Reason::TooSmall)]);
+    }
+
+    #[test]
+    fn log_capture_collects_detection_lines() {
+        let repo = TempRepo::new();
+        let settings = repo.write(
+            "settings.xml",
+            format!(
+                "<settings><localRepository>{}</localRepository></settings>",
+                repo.path().display()
+            ),
+        );
+        let state = AppState::default();
+
+        let ((location, inner), outer) = with_log_capture(|| {
+            log_msg!("外层", "outer");
+            with_log_capture(|| {
+                detect_repo_location(
+                    &state,
+                    Some(settings.to_string_lossy().into_owned()),
+                    &isolated_detect_env(),
+                )
+            })
+        });
+        assert_eq!(location.unwrap().source, RepoSource::CustomSettings);
+        assert!(inner
+            .iter()
+            .any(|line| line.contains("[步骤 0.5]") || line.contains("[Step 0.5]")));
+        let detected = repo.path().display().to_string();
+        assert!(inner.iter().any(|line| line.contains(&detected)));
+        // 内层收集结束后恢复外层，检测日志不会重复进入外层
+        assert_eq!(outer.len(), 1);
+    }
+
+    #[test]
+    fn version_dir_matches_file_version() {
+        assert!(matches_version_dir("1.0", "1.0"));
+        assert!(matches_version_dir("1.0-sources", "1.0"));
+        assert!(!matches_version_dir("1.0.1", "1.0"));
+        assert!(matches_version_dir("1.0-20240101.120000-3", "1.0-SNAPSHOT"));
+        assert!(matches_version_dir(
+            "1.0-20240101.120000-3-sources",
+            "1.0-SNAPSHOT"
+        ));
+        assert!(!matches_version_dir("1.0-2024-3", "1.0-SNAPSHOT"));
+
+        let repo = TempRepo::new();
+        let check = |relative: &str| {
+            let path = repo.path().join(relative);
+            let file_name = path.file_name().unwrap().to_str().unwrap().to_string();
+            reason_of(check_version_dir(&path, &file_name))
+        };
+        assert_eq!(
+            check("com/example/foo/1.0/foo-2.0.jar"),
+            Some(Reason::VersionMismatch)
+        );
+        assert_eq!(check("com/example/foo/1.0/foo-1.0.jar"), None);
+        assert_eq!(check("com/example/foo/1.0/foo-1.0-sources.jar"), None);
+        assert_eq!(
+            check("com/example/foo/1.0-SNAPSHOT/foo-1.0-20240101.120000-3.jar"),
+            None
+        );
+        // 不以 artifactId 开头的文件不按此规则检查
+        assert_eq!(check("com/example/foo/1.0/bar-2.0.jar"), None);
+    }
+
+    #[test]
+    fn too_many_files_refuses_scan_unless_forced() {
+        let repo = TempRepo::new();
+        for i in 0..3 {
+            repo.write(&format!("com/example/a{i}/1.0/a{i}-1.0.jar"), valid_jar());
+        }
+        let config = ScanConfig {
+            max_files: 2,
+            ..ScanConfig::default()
+        };
+        let err = run_scan(repo.path(), config.clone(), &ScanPause::default()).unwrap_err();
+        assert!(err.starts_with(SCAN_REFUSED_PREFIX), "{}", err);
+
+        let config = ScanConfig {
+            force: true,
+            ..config
+        };
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.files_scanned, 3);
+        assert_eq!(report.total_invalid, 0);
+    }
+
+    #[test]
+    fn low_open_file_limit_checks_every_file() {
+        let repo = TempRepo::new();
+        for i in 0..20 {
+            repo.write(&format!("com/example/a{i}/1.0/a{i}-1.0.jar"), b"not a zip");
+            repo.write(&format!("com/example/a{i}/1.0/a{i}-1.0.pom"), HARBOR_POM);
+        }
+        let config = ScanConfig {
+            max_open_files: 1,
+            thread_count: Some(8),
+            ..ScanConfig::default()
+        };
+        let report = run_scan(repo.path(), config, &ScanPause::default()).unwrap();
+        assert_eq!(report.files_scanned, 40);
+        assert_eq!(report.total_invalid, 40);
+        assert_eq!(report.reason_counts.get(&Reason::CorruptZip), Some(&20));
+        assert_eq!(report.reason_counts.get(&Reason::HarborPom), Some(&20));
+    }
+
+    #[test]
+    fn remove_with_retry_retries_only_transient_errors() {
+        use std::io::{Error, ErrorKind};
+
+        let mut calls = 0;
+        let result = remove_with_retry(
+            || {
+                calls += 1;
+                if calls <= 2 {
+                    Err(Error::from(ErrorKind::PermissionDenied))
+                } else {
+                    Ok(())
+                }
+            },
+            3,
+        );
+        assert!(result.is_ok());
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let result = remove_with_retry(
+            || {
+                calls += 1;
+                Err(Error::from(ErrorKind::NotFound))
+            },
+            3,
+        );
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::NotFound);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn remove_command_quotes_special_characters() {
+        assert_eq!(
+            ScriptPlatform::Bash.remove_command("/repo/my dir/it's $HOME `x`.jar"),
+            r"rm -f -- '/repo/my dir/it'\''s $HOME `x`.jar'"
+        );
+        // 单引号字符串中 `$` 和反引号不会展开，只需双写单引号（含弯引号）
+        assert_eq!(
+            ScriptPlatform::Powershell.remove_command("C:\\repo\\my dir\\it's $HOME `x` \u{2019}.jar"),
+            "Remove-Item -LiteralPath 'C:\\repo\\my dir\\it''s $HOME `x` \u{2019}\u{2019}.jar' -Force"
+        );
+    }
+
+    #[test]
+    fn gradle_scan_applies_maven_size_thresholds_and_file_limit() {
+        let repo = TempRepo::new();
+        let small = zip_bytes(&[("com/example/Foo.class", &[0xca; 300])]);
+        assert!((100..1024).contains(&small.len()));
+        let dir = "files-2.1/com.example/foo/1.0";
+        let main = repo.write(&format!("{}/0123abcd/foo-1.0.jar", dir), &small);
+        let sources = repo.write(&format!("{}/4567cdef/foo-1.0-sources.jar", dir), &small);
+        assert_eq!(jar_classifier(&main, "foo-1.0.jar"), Some(""));
+        assert_eq!(
+            jar_classifier(&sources, "foo-1.0-sources.jar"),
+            Some("sources")
+        );
+
+        // 与 Maven 仓库相同：主 JAR 按 1KB 下限检查，sources 不检查
+        let report =
+            run_gradle_scan(repo.path(), ScanConfig::default(), &ScanPause::default()).unwrap();
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.artifacts.len(), 1);
+        assert_eq!(report.artifacts[0].base_name, "foo-1.0");
+        assert_eq!(report.artifacts[0].reason, Reason::TooSmall);
+
+        let config = ScanConfig {
+            max_files: 1,
+            ..ScanConfig::default()
+        };
+        let err = run_gradle_scan(repo.path(), config.clone(), &ScanPause::default()).unwrap_err();
+        assert!(err.starts_with(SCAN_REFUSED_PREFIX), "{}", err);
+        let config = ScanConfig {
+            force: true,
+            ..config
+        };
+        assert!(run_gradle_scan(repo.path(), config, &ScanPause::default()).is_ok());
+    }
+
+    #[test]
+    fn pause_succeeds_when_event_emit_fails() {
+        let state = AppState::default();
+        let (result, lines) = with_log_capture(|| {
+            set_scan_paused(&state, true, |_, _| Err("window closed".to_string()))
+        });
+        assert!(result.is_ok());
+        assert!(state.scan_pause.is_paused());
+        assert!(lines.iter().any(|line| line.contains("window closed")));
+
+        // 状态未变化时不发送事件
+        let mut emitted = 0;
+        set_scan_paused(&state, true, |_, _| {
+            emitted += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(emitted, 0);
+        set_scan_paused(&state, false, |event, paused| {
+            assert_eq!((event, paused), (SCAN_PAUSE_EVENT, false));
+            emitted += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(emitted, 1);
+        assert!(!state.scan_pause.is_paused());
+    }
+
+    #[test]
+    fn version_mismatch_check_is_opt_in() {
+        let repo = TempRepo::new();
+        repo.write("com/example/foo/1.0/foo-2.0.jar", valid_jar());
+        let pause = ScanPause::default();
+
+        let report = run_scan(repo.path(), ScanConfig::default(), &pause).unwrap();
+        assert_eq!(report.total_invalid, 0);
+
+        let config = ScanConfig {
+            check_version_mismatch: true,
+            ..ScanConfig::default()
+        };
+        let report = run_scan(repo.path(), config, &pause).unwrap();
+        assert_eq!(report.total_invalid, 1);
+    }
+
+    #[test]
+    fn scan_refuses_paths_without_repo_directory() {
+        assert!(looks_like_repo_path(Path::new("/home/u/.m2/repository")));
+        assert!(looks_like_repo_path(Path::new("/opt/repository/com")));
+        assert!(!looks_like_repo_path(Path::new("/home/u/reports")));
+        assert!(!looks_like_repo_path(Path::new("/home/u/my-repos-backup")));
+        assert!(!looks_like_repo_path(Path::new("/home/u/reporting/2024")));
+        assert_eq!(
+            looks_like_repo_path(Path::new("/home/u/Repository")),
+            cfg!(windows)
+        );
+
+        let repo = TempRepo::new();
+        let reports = repo.dir.join("reports");
+        fs::create_dir_all(&reports).unwrap();
+        fs::write(reports.join("foo-1.0.jar"), b"bad").unwrap();
+        let err = run_scan(&reports, ScanConfig::default(), &ScanPause::default()).unwrap_err();
+        assert!(err.starts_with(SCAN_REFUSED_PREFIX), "{}", err);
+
+        let config = ScanConfig {
+            force: true,
+            ..ScanConfig::default()
+        };
+        let report = run_scan(&reports, config, &ScanPause::default()).unwrap();
+        assert_eq!(report.total_invalid, 1);
+    }
 }